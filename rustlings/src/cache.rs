@@ -0,0 +1,153 @@
+use crate::exercise::{Exercise, Mode};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::OnceLock;
+use std::thread;
+
+const CACHE_DIR: &str = ".rustlings-cache";
+
+// The output of `rustc --version`, so that binaries built by
+// another toolchain are never reused
+fn rustc_version() -> &'static str {
+    static VERSION: OnceLock<String> = OnceLock::new();
+    VERSION.get_or_init(|| {
+        Command::new("rustc")
+            .arg("--version")
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .unwrap_or_default()
+    })
+}
+
+// Where cargo builds Cargo exercises and their dependencies
+pub fn cargo_target_dir() -> PathBuf {
+    Path::new(CACHE_DIR).join("target")
+}
+
+// Where scratch copies of exercises are made, e.g. to try their solutions
+pub fn scratch_dir() -> PathBuf {
+    Path::new(CACHE_DIR).join("scratch")
+}
+
+// Where the checkouts of students are graded, each in a directory of its own
+pub fn grade_dir() -> PathBuf {
+    Path::new(CACHE_DIR).join("grade")
+}
+
+// The cache key of an exercise: anything that would change
+// the outcome of compiling it
+fn key(exercise: &Exercise) -> io::Result<String> {
+    let source = fs::read(&exercise.path)?;
+    let mode = match exercise.mode {
+        Mode::Compile => "compile",
+        Mode::Test => "test",
+        Mode::Clippy => "clippy",
+        Mode::Cargo => "cargo",
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(&source);
+    hasher.update(mode.as_bytes());
+    hasher.update(rustc_version().as_bytes());
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+// Every exercise keeps at most one cached binary in its own directory
+fn entry_dir(exercise: &Exercise) -> PathBuf {
+    Path::new(CACHE_DIR).join(&exercise.name)
+}
+
+// The binary of a previous successful compilation of the exercise,
+// if its source, mode and toolchain haven't changed since
+pub fn lookup(exercise: &Exercise) -> Option<PathBuf> {
+    let path = entry_dir(exercise).join(key(exercise).ok()?);
+    if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+// Remember the binary of a successful compilation of the exercise,
+// replacing the one of any earlier version of its source. The binary is
+// copied next to its entry first and then renamed onto it, so that other
+// workers or processes never find it half-copied.
+pub fn store(exercise: &Exercise, binary: &Path) -> io::Result<()> {
+    let key = key(exercise)?;
+    let dir = entry_dir(exercise);
+    fs::create_dir_all(&dir)?;
+    let thread_id: String = format!("{:?}", thread::current().id())
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect();
+    let partial = dir.join(format!("{}.{}.{}.tmp", key, process::id(), thread_id));
+    if let Err(e) = fs::copy(binary, &partial) {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, dir.join(&key))?;
+
+    // Readers copy the binaries out of the cache before running them, so
+    // the ones of earlier versions can go, unlike the ones still being copied
+    for entry in fs::read_dir(&dir)?.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name != key && !name.ends_with(".tmp") {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn exercise(name: &str, mode: Mode) -> Exercise {
+        Exercise {
+            name: name.into(),
+            path: PathBuf::from("tests/fixture/success/compSuccess.rs"),
+            mode,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_key_depends_on_mode() {
+        let compile = exercise("cache_key", Mode::Compile);
+        let test = exercise("cache_key", Mode::Test);
+        assert_ne!(key(&compile).unwrap(), key(&test).unwrap());
+    }
+
+    #[test]
+    fn test_lookup_finds_stored_binary() {
+        let exercise = exercise("cache_roundtrip", Mode::Compile);
+        let _ = fs::remove_dir_all(entry_dir(&exercise));
+        assert_eq!(lookup(&exercise), None);
+
+        store(&exercise, Path::new("tests/fixture/success/info.toml")).unwrap();
+        let cached = lookup(&exercise).unwrap();
+        assert_eq!(
+            fs::read(cached).unwrap(),
+            fs::read("tests/fixture/success/info.toml").unwrap()
+        );
+        fs::remove_dir_all(entry_dir(&exercise)).unwrap();
+    }
+
+    #[test]
+    fn test_store_replaces_earlier_binary() {
+        let compile = exercise("cache_replace", Mode::Compile);
+        let test = exercise("cache_replace", Mode::Test);
+        let _ = fs::remove_dir_all(entry_dir(&compile));
+
+        store(&compile, Path::new("tests/fixture/success/info.toml")).unwrap();
+        store(&test, Path::new("tests/fixture/success/info.toml")).unwrap();
+        assert_eq!(lookup(&compile), None);
+        assert!(lookup(&test).is_some());
+        assert_eq!(fs::read_dir(entry_dir(&test)).unwrap().count(), 1);
+        fs::remove_dir_all(entry_dir(&test)).unwrap();
+    }
+}
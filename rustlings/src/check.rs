@@ -0,0 +1,168 @@
+use crate::cache;
+use crate::exercise::Exercise;
+use crate::reset;
+use crate::verify::{details, evaluate, Outcome};
+use indicatif::ProgressBar;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Make sure that the given exercises can be solved: their reference
+// solutions have to pass, while the exercises as shipped must not.
+// Meant for those who write or patch exercises, not for learners.
+pub fn check_solutions(exercises: &[&Exercise]) -> Result<(), ()> {
+    let with_solution: Vec<&Exercise> = exercises
+        .iter()
+        .copied()
+        .filter(|e| e.solution.is_some())
+        .collect();
+    if with_solution.is_empty() {
+        warn!("None of the {} exercises has a solution", exercises.len());
+        return Err(());
+    }
+
+    let mut broken = 0;
+    for exercise in &with_solution {
+        let progress_bar = ProgressBar::new_spinner();
+        progress_bar.set_message(format!("Checking the solution of {}...", exercise));
+        progress_bar.enable_steady_tick(100);
+        let problem = check(exercise, &progress_bar);
+        progress_bar.finish_and_clear();
+
+        match problem {
+            None => success!("{} and its solution check out", exercise),
+            Some(problem) => {
+                broken += 1;
+                warn!("{}", problem);
+            }
+        }
+    }
+
+    println!(
+        "{} of {} solutions check out.",
+        with_solution.len() - broken,
+        with_solution.len()
+    );
+    let without_solution = exercises.len() - with_solution.len();
+    if without_solution > 0 {
+        println!("{} exercises have no solution to check.", without_solution);
+    }
+    if broken > 0 {
+        return Err(());
+    }
+    Ok(())
+}
+
+// What's wrong with the exercise or its solution, if anything
+fn check(exercise: &Exercise, progress_bar: &ProgressBar) -> Option<String> {
+    let solved = match solved_copy(exercise) {
+        Ok(solved) => solved,
+        Err(e) => {
+            return Some(format!(
+                "Could not make a solved copy of {}: {}",
+                exercise, e
+            ))
+        }
+    };
+    if !solved.looks_done() {
+        return Some(format!(
+            "The solution of {} still has the `I AM NOT DONE` comment",
+            exercise
+        ));
+    }
+    match evaluate(&solved, progress_bar) {
+        Outcome::Passed(_) => {}
+        outcome => {
+            return Some(format!(
+                "The solution of {} doesn't pass:\n{}",
+                exercise,
+                details(&solved, &outcome)
+            ))
+        }
+    }
+
+    let shipped = match shipped_copy(exercise) {
+        Ok(shipped) => shipped,
+        Err(e) => {
+            return Some(format!(
+                "Could not make a copy of {} as shipped: {}",
+                exercise, e
+            ))
+        }
+    };
+    if let Outcome::Passed(_) = evaluate(&shipped, progress_bar) {
+        return Some(format!(
+            "{} passes as it is, there is nothing for learners to fix",
+            exercise
+        ));
+    }
+    None
+}
+
+// A scratch copy of the exercise with the files of its solution in place
+// of the learner's. The exercise itself is left untouched.
+fn solved_copy(exercise: &Exercise) -> io::Result<Exercise> {
+    let files = exercise
+        .source_files()
+        .into_iter()
+        .map(|file| (file.clone(), file))
+        .chain(exercise.solution_files())
+        .map(|(file, source)| Ok((file, fs::read(source)?)))
+        .collect::<io::Result<_>>()?;
+    scratch_copy(exercise, "solved", files)
+}
+
+// A scratch copy of the exercise the way it was shipped, made from the
+// originals kept for `rustlings reset`, so that the learner's progress
+// doesn't matter. Files without an original are copied as they are.
+fn shipped_copy(exercise: &Exercise) -> io::Result<Exercise> {
+    let files = exercise
+        .source_files()
+        .into_iter()
+        .map(|file| {
+            let contents = match reset::original(&file) {
+                Some(original) => original.into_bytes(),
+                None => fs::read(&file)?,
+            };
+            Ok((file, contents))
+        })
+        .collect::<io::Result<_>>()?;
+    scratch_copy(exercise, "shipped", files)
+}
+
+// A copy of the exercise in a scratch directory of the given kind, made of
+// the given files of the exercise with the given contents
+fn scratch_copy(
+    exercise: &Exercise,
+    kind: &str,
+    files: Vec<(PathBuf, Vec<u8>)>,
+) -> io::Result<Exercise> {
+    let scratch = cache::scratch_dir().join(kind).join(&exercise.name);
+    if scratch.exists() {
+        fs::remove_dir_all(&scratch)?;
+    }
+
+    let relative = |file: &Path| -> PathBuf {
+        match file.strip_prefix(&exercise.path) {
+            Ok(relative) if exercise.is_cargo() => relative.to_path_buf(),
+            _ => PathBuf::from(file.file_name().unwrap_or_default()),
+        }
+    };
+    for (file, contents) in files {
+        let copy = scratch.join(relative(&file));
+        if let Some(parent) = copy.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&copy, contents)?;
+    }
+
+    let path = if exercise.is_cargo() {
+        scratch
+    } else {
+        scratch.join(relative(&exercise.path))
+    };
+    Ok(Exercise {
+        path,
+        ..exercise.clone()
+    })
+}
@@ -0,0 +1,96 @@
+use crate::exercise::ExerciseList;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Where courses are installed, relative to the home directory.
+// RUSTLINGS_COURSES_DIR takes its place if it is set.
+const COURSES_DIR: &str = ".rustlings/courses";
+
+// A course pack: a directory with its own info.toml, exercises and progress
+pub struct Course {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+impl Course {
+    // How many exercises the course has, if its info.toml can be read
+    pub fn exercise_count(&self) -> Option<usize> {
+        let toml_str = fs::read_to_string(self.dir.join("info.toml")).ok()?;
+        let list = toml::from_str::<ExerciseList>(&toml_str).ok()?;
+        Some(list.exercises.len())
+    }
+}
+
+pub fn courses_dir() -> Option<PathBuf> {
+    match env::var_os("RUSTLINGS_COURSES_DIR") {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => home::home_dir().map(|home| home.join(COURSES_DIR)),
+    }
+}
+
+// The installed courses, sorted by name
+pub fn installed() -> Vec<Course> {
+    courses_dir()
+        .map(|dir| installed_in(&dir))
+        .unwrap_or_default()
+}
+
+fn installed_in(dir: &Path) -> Vec<Course> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut courses: Vec<Course> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|dir| dir.join("info.toml").is_file())
+        .map(|dir| Course {
+            name: dir.file_name().unwrap_or_default().to_string_lossy().into(),
+            dir,
+        })
+        .collect();
+    courses.sort_by(|a, b| a.name.cmp(&b.name));
+    courses
+}
+
+// The directory of the given course: either a directory with an info.toml,
+// or the name of an installed course
+pub fn find(course: &str) -> Result<PathBuf, String> {
+    find_in(course, &installed())
+}
+
+fn find_in(course: &str, installed: &[Course]) -> Result<PathBuf, String> {
+    let dir = Path::new(course);
+    if dir.join("info.toml").is_file() {
+        return Ok(dir.to_path_buf());
+    }
+    match installed.iter().find(|c| c.name == course) {
+        Some(installed) => Ok(installed.dir.clone()),
+        None => Err(format!(
+            "{} is neither a directory with an info.toml nor an installed course",
+            course
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_course_by_directory_or_name() {
+        let installed = installed_in(Path::new("tests/fixture"));
+        assert!(installed.iter().any(|c| c.name == "success"));
+
+        assert_eq!(
+            find_in("tests/fixture/state", &installed),
+            Ok(PathBuf::from("tests/fixture/state"))
+        );
+        assert_eq!(
+            find_in("success", &installed),
+            Ok(PathBuf::from("tests/fixture/success"))
+        );
+        assert!(find_in("nothing", &installed).is_err());
+    }
+}
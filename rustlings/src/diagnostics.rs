@@ -0,0 +1,252 @@
+use console::style;
+use serde::Deserialize;
+use std::process::Command;
+
+// A diagnostic emitted by rustc with `--error-format=json`.
+// Only the fields rustlings uses are deserialized.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Diagnostic {
+    // The primary message, e.g. "mismatched types"
+    pub message: String,
+    // The error code, e.g. E0308, along with its explanation
+    pub code: Option<DiagnosticCode>,
+    // "error", "warning", "note", "help", "failure-note", ...
+    pub level: String,
+    // The places in the source code this diagnostic points at
+    #[serde(default)]
+    pub spans: Vec<DiagnosticSpan>,
+    // Attached notes and suggestions
+    #[serde(default)]
+    pub children: Vec<Diagnostic>,
+    // The diagnostic as rustc would have printed it, with colors
+    pub rendered: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct DiagnosticCode {
+    pub code: String,
+    pub explanation: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct DiagnosticSpan {
+    pub file_name: String,
+    pub line_start: usize,
+    pub column_start: usize,
+    pub is_primary: bool,
+    // The code the compiler suggests to put at this span
+    pub suggested_replacement: Option<String>,
+}
+
+// Cargo wraps the diagnostics of rustc in messages of its own
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<Diagnostic>,
+}
+
+impl Diagnostic {
+    // Whether this is an actual error, and not just the
+    // "aborting due to previous errors" summary
+    pub fn is_error(&self) -> bool {
+        self.level.starts_with("error") && !self.message.starts_with("aborting due to")
+    }
+
+    pub fn primary_span(&self) -> Option<&DiagnosticSpan> {
+        self.spans.iter().find(|span| span.is_primary)
+    }
+
+    // Replacements the compiler suggests to fix this diagnostic
+    pub fn suggestions(&self) -> impl Iterator<Item = &DiagnosticSpan> {
+        self.children
+            .iter()
+            .flat_map(|child| child.spans.iter())
+            .filter(|span| span.suggested_replacement.is_some())
+    }
+
+    // A single line describing the diagnostic, e.g.
+    // "error[E0425]: cannot find value `x` in this scope (intro2.rs:4:5)",
+    // followed by what the compiler suggests to fix it
+    pub fn summary(&self) -> String {
+        let mut summary = match &self.code {
+            Some(code) => format!("{}[{}]: {}", self.level, code.code, self.message),
+            None => format!("{}: {}", self.level, self.message),
+        };
+        if let Some(span) = self.primary_span() {
+            summary += &format!(
+                " ({}:{}:{})",
+                span.file_name, span.line_start, span.column_start
+            );
+        }
+        for suggestion in self.suggestions() {
+            if let Some(replacement) = &suggestion.suggested_replacement {
+                summary += &format!(" - try `{}`", replacement.trim());
+            }
+        }
+        summary
+    }
+}
+
+// Split the output of rustc or cargo into the diagnostics it contains,
+// and the text a human would have seen without `--error-format=json`
+pub fn parse(output: &str) -> (Vec<Diagnostic>, String) {
+    let mut diagnostics = Vec::new();
+    let mut text = String::new();
+    for line in output.lines() {
+        let diagnostic = serde_json::from_str::<Diagnostic>(line).ok().or_else(|| {
+            serde_json::from_str::<CargoMessage>(line)
+                .ok()
+                .filter(|message| message.reason == "compiler-message")
+                .and_then(|message| message.message)
+        });
+        match diagnostic {
+            Some(diagnostic) => {
+                text += diagnostic.rendered.as_deref().unwrap_or_default();
+                diagnostics.push(diagnostic);
+            }
+            // Cargo's own messages about building or aborting
+            None if !line.starts_with('{') => {
+                text += line;
+                text.push('\n');
+            }
+            None => {}
+        }
+    }
+    (diagnostics, text)
+}
+
+// Print compiler errors in a condensed way, see `condense`.
+// Falls back to the full text if there is nothing to condense
+// or `show_all` is set.
+pub fn print_errors(diagnostics: &[Diagnostic], text: &str, show_all: bool) {
+    let errors = errors(diagnostics);
+    let first = match errors.first() {
+        Some(first) if !show_all => first,
+        _ => {
+            println!("{}", text);
+            return;
+        }
+    };
+
+    println!("{}", condense(&errors));
+    if errors.len() > 1 {
+        println!("Use `--nocapture` (or type `errors` in watch mode) to see them in full.");
+    }
+    // Only rustc's own error codes have an explanation, Clippy's lints don't
+    if let Some(code) = first.code.as_ref().filter(|c| c.explanation.is_some()) {
+        println!(
+            "Run `rustlings explain {}` (or type `explain` in watch mode) to learn more about this error.",
+            code.code
+        );
+    }
+    println!();
+}
+
+// The actual errors among the diagnostics, see `Diagnostic::is_error`
+pub fn errors(diagnostics: &[Diagnostic]) -> Vec<&Diagnostic> {
+    diagnostics.iter().filter(|d| d.is_error()).collect()
+}
+
+// The first of the given errors in full, and only a summary of the others,
+// as a single typo often causes many follow-up errors
+pub fn condense(errors: &[&Diagnostic]) -> String {
+    let (first, others) = match errors.split_first() {
+        Some(split) => split,
+        None => return String::new(),
+    };
+
+    let mut text = first
+        .rendered
+        .as_deref()
+        .unwrap_or(&first.message)
+        .trim_end()
+        .to_string();
+    if !others.is_empty() {
+        text += &format!(
+            "\n\n{}",
+            style(format!(
+                "... and {} more {}, often caused by the first one:",
+                others.len(),
+                if others.len() == 1 { "error" } else { "errors" }
+            ))
+            .bold()
+        );
+        for error in others {
+            text += &format!("\n  {}", style(error.summary()).dim());
+        }
+    }
+    text
+}
+
+// Print rustc's detailed explanation of an error code, e.g. E0308
+pub fn explain(code: &str) -> Result<(), ()> {
+    match explanation(code) {
+        Ok(text) => {
+            println!("{}", text);
+            Ok(())
+        }
+        Err(text) => {
+            println!("{}", text);
+            Err(())
+        }
+    }
+}
+
+// The text `rustc --explain` has for the given error code
+pub fn explanation(code: &str) -> Result<String, String> {
+    let output = Command::new("rustc")
+        .args(["--explain", code])
+        .output()
+        .expect("Failed to run 'rustc --explain'");
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const RUSTC_OUTPUT: &str = r#"{"message":"cannot find value `x` in this scope","code":{"code":"E0425","explanation":"An unresolved name was used."},"level":"error","spans":[{"file_name":"intro2.rs","line_start":4,"column_start":20,"is_primary":true,"suggested_replacement":null}],"children":[],"rendered":"error[E0425]: cannot find value `x` in this scope\n"}
+{"message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting due to 1 previous error\n\n"}
+"#;
+
+    #[test]
+    fn test_parse_rustc_output() {
+        let (diagnostics, text) = parse(RUSTC_OUTPUT);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[0].is_error());
+        assert!(!diagnostics[1].is_error());
+        assert_eq!(
+            diagnostics[0].summary(),
+            "error[E0425]: cannot find value `x` in this scope (intro2.rs:4:20)"
+        );
+        assert_eq!(
+            text,
+            "error[E0425]: cannot find value `x` in this scope\nerror: aborting due to 1 previous error\n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_cargo_output() {
+        let output = format!(
+            "    Checking clippy1 v0.0.1\n{}\n{}\n",
+            r#"{"reason":"compiler-artifact","package_id":"clippy1"}"#,
+            r#"{"reason":"compiler-message","message":{"message":"approximate value of `f32::consts::PI` found","code":{"code":"clippy::approx_constant","explanation":null},"level":"error","spans":[],"children":[{"message":"consider using the constant directly","code":null,"level":"help","spans":[{"file_name":"clippy1.rs","line_start":14,"column_start":14,"is_primary":true,"suggested_replacement":"std::f32::consts::PI"}],"children":[],"rendered":null}],"rendered":"error: approximate value\n"}}"#
+        );
+        let (diagnostics, text) = parse(&output);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].summary(),
+            "error[clippy::approx_constant]: approximate value of `f32::consts::PI` found - try `std::f32::consts::PI`"
+        );
+        assert_eq!(
+            text,
+            "    Checking clippy1 v0.0.1\nerror: approximate value\n"
+        );
+    }
+}
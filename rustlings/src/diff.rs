@@ -0,0 +1,102 @@
+use console::style;
+use similar::{ChangeTag, DiffTag, TextDiff};
+
+const CONTEXT_LINES: usize = 2;
+
+// Print a colored line diff between two texts, see `diff`
+pub fn print_diff(old: &str, new: &str) {
+    print!("{}", diff(old, new));
+}
+
+// A colored line diff between two texts.
+// Removed lines are red and prefixed with `-`,
+// added lines are green and prefixed with `+`.
+pub fn diff(old: &str, new: &str) -> String {
+    // A missing newline at the very end shouldn't make the last line differ
+    let (old, new) = (terminated(old), terminated(new));
+    let diff = TextDiff::from_lines(&old, &new);
+    let mut text = String::new();
+    for (i, group) in diff.grouped_ops(CONTEXT_LINES).iter().enumerate() {
+        if i > 0 {
+            text += &format!("{}\n", style("...").dim());
+        }
+        for op in group {
+            for change in diff.iter_changes(op) {
+                let line = change.value().trim_end_matches(['\r', '\n']);
+                text += &match change.tag() {
+                    ChangeTag::Delete => format!("{}\n", style(format!("-{}", line)).red()),
+                    ChangeTag::Insert => format!("{}\n", style(format!("+{}", line)).green()),
+                    ChangeTag::Equal => format!(" {}\n", line),
+                };
+            }
+        }
+    }
+    text
+}
+
+// Two texts next to each other, with the differing lines in color.
+// Lines are cut off to fit the given width.
+pub fn side_by_side(old: &str, new: &str, width: usize) -> String {
+    let column = width.saturating_sub(3) / 2;
+    let (old_lines, new_lines): (Vec<&str>, Vec<&str>) =
+        (old.lines().collect(), new.lines().collect());
+    let diff = TextDiff::from_lines(old, new);
+    let mut text = String::new();
+    for op in diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        for i in 0..old_range.len().max(new_range.len()) {
+            let left = old_range.clone().nth(i).map(|i| old_lines[i]);
+            let right = new_range.clone().nth(i).map(|i| new_lines[i]);
+            let (left, right) = (fit(left, column), fit(right, column));
+            text += &match tag {
+                DiffTag::Equal => format!("{} │ {}\n", left, right),
+                _ => format!(
+                    "{} {} {}\n",
+                    style(left).red(),
+                    style("│").yellow(),
+                    style(right).green()
+                ),
+            };
+        }
+    }
+    text
+}
+
+// Pad or cut off a line to exactly the given number of characters
+fn fit(line: Option<&str>, width: usize) -> String {
+    let line = line
+        .map(|line| line.replace('\t', "    "))
+        .unwrap_or_default();
+    let fitted: String = line.chars().take(width).collect();
+    format!("{:<width$}", fitted, width = width)
+}
+
+fn terminated(text: &str) -> String {
+    if text.is_empty() || text.ends_with('\n') {
+        text.to_string()
+    } else {
+        format!("{}\n", text)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_side_by_side() {
+        let old = "fn main() {\n    println!(\"{}\", x);\n}\n";
+        let new = "fn main() {\n    let x = 5;\n    println!(\"{}\", x);\n}\n";
+
+        let text = console::strip_ansi_codes(&side_by_side(old, new, 43)).to_string();
+        assert_eq!(
+            text.lines().collect::<Vec<_>>(),
+            vec![
+                "fn main() {          │ fn main() {         ",
+                "                     │     let x = 5;      ",
+                "    println!(\"{}\", x │     println!(\"{}\", x",
+                "}                    │ }                   ",
+            ]
+        );
+    }
+}
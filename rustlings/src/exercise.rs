@@ -1,17 +1,37 @@
+use crate::cache;
+use crate::diagnostics::{self, Diagnostic};
+use crate::lint;
+use crate::rules::Construct;
+use glob::glob;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
 use std::env;
 use std::fmt::{self, Display, Formatter};
 use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
-use std::process::{self, Command};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::{self, Child, Command, ExitStatus, Stdio};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
 const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
+// Let rustc report its diagnostics as JSON, while still rendering them in color
+const RUSTC_JSON_ARGS: &[&str] = &["--error-format=json", "--json=diagnostic-rendered-ansi"];
 const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
 const CONTEXT: usize = 2;
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+// Exercise binaries can't allocate more memory than this
+#[cfg(target_os = "linux")]
+const MEMORY_LIMIT_BYTES: u64 = 1 << 30;
 const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
 
+// All Clippy exercises share one Cargo.toml, so only one of them
+// may be compiled at a time
+static CLIPPY_LOCK: Mutex<()> = Mutex::new(());
+
 // Get a temporary file name that is hopefully unique
 #[inline]
 fn temp_file() -> String {
@@ -24,15 +44,82 @@ fn temp_file() -> String {
 }
 
 // The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Serialize, Copy, Clone, Default, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     // Indicates that the exercise should be compiled as a binary
+    #[default]
     Compile,
     // Indicates that the exercise should be compiled as a test harness
     Test,
     // Indicates that the exercise should be linted with clippy
     Clippy,
+    // Indicates that the exercise is a directory with its own Cargo.toml,
+    // which should be built and tested with `cargo test`
+    Cargo,
+}
+
+// Text that is either written inline in info.toml, e.g. `"some text"`,
+// or kept in a file, e.g. `{ file = "path/to/file" }`. Like the paths of
+// the exercises, the path is relative to the directory of info.toml.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum TextSource {
+    Inline(String),
+    File { file: PathBuf },
+}
+
+impl TextSource {
+    // The text, or why its file couldn't be read
+    pub fn read(&self) -> Result<String, String> {
+        match self {
+            TextSource::Inline(text) => Ok(text.clone()),
+            TextSource::File { file } => fs::read_to_string(file)
+                .map_err(|e| format!("Could not read {}: {}", file.display(), e)),
+        }
+    }
+}
+
+// A scripted run of a compile mode exercise: the text that is piped to its
+// standard input, and what it then has to print to pass
+#[derive(Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct Case {
+    #[serde(default)]
+    pub input: Option<TextSource>,
+    #[serde(default)]
+    pub expected_output: Option<TextSource>,
+}
+
+impl Case {
+    // The case with the contents of its files in place of them, so that
+    // a file that went missing is noticed before the exercise is run
+    pub fn load(&self) -> Result<Case, String> {
+        let load = |source: &Option<TextSource>| {
+            source
+                .as_ref()
+                .map(|source| source.read().map(TextSource::Inline))
+                .transpose()
+        };
+        Ok(Case {
+            input: load(&self.input)?,
+            expected_output: load(&self.expected_output)?,
+        })
+    }
+
+    // Whether the given output is what the exercise is expected to print.
+    // Returns the expected output otherwise, to show what's different,
+    // or why it couldn't be read.
+    pub fn check_output(&self, stdout: &str) -> Result<(), String> {
+        let expected = match &self.expected_output {
+            Some(expected) => normalize_output(&expected.read()?),
+            None => return Ok(()),
+        };
+        if normalize_output(stdout) == expected {
+            Ok(())
+        } else {
+            Err(expected)
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -40,18 +127,148 @@ pub struct ExerciseList {
     pub exercises: Vec<Exercise>,
 }
 
+impl ExerciseList {
+    // Read the exercises from the given info.toml and make sure they make
+    // sense. All of the problems that were found are in the error.
+    pub fn load(path: &Path) -> Result<Vec<Exercise>, String> {
+        let toml_str = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        lint::parse(&toml_str).map_err(|problems| {
+            let problems: Vec<String> = problems.iter().map(|p| p.describe(path)).collect();
+            format!("{} is invalid:\n{}", path.display(), problems.join("\n"))
+        })
+    }
+}
+
+// Where the exercises of a course are: the top level directories they are in,
+// e.g. `exercises`, or the exercise files themselves if they are right next
+// to info.toml. Course packs are free to lay out their exercises otherwise.
+pub fn roots(exercises: &[Exercise]) -> BTreeSet<PathBuf> {
+    let mut roots = BTreeSet::new();
+    for exercise in exercises {
+        let mut root = PathBuf::new();
+        for component in exercise.path.components() {
+            match component {
+                Component::CurDir => {}
+                Component::Normal(_) => {
+                    root.push(component);
+                    break;
+                }
+                _ => root.push(component),
+            }
+        }
+        roots.insert(root);
+    }
+    roots
+}
+
+// The Rust files below the given roots, leaving out build artifacts
+pub fn rust_files(roots: &BTreeSet<PathBuf>) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for root in roots {
+        if !root.is_dir() {
+            if root.extension().is_some_and(|ext| ext == "rs") {
+                files.push(root.clone());
+            }
+            continue;
+        }
+        let pattern = root.join("**").join("*.rs");
+        files.extend(
+            glob(&pattern.to_string_lossy())
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(|file| !file.components().any(|c| c.as_os_str() == "target")),
+        );
+    }
+    files
+}
+
+// The exercises that appeared in or disappeared from info.toml when it was
+// loaded again, along with what went wrong while taking them in
+#[derive(PartialEq, Debug)]
+pub struct ListChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ListChanges {
+    pub fn between(old: &[Exercise], new: &[Exercise]) -> ListChanges {
+        let names = |exercises: &[Exercise]| -> Vec<String> {
+            exercises.iter().map(|e| e.name.clone()).collect()
+        };
+        let (old, new) = (names(old), names(new));
+        ListChanges {
+            added: new.iter().filter(|n| !old.contains(n)).cloned().collect(),
+            removed: old.iter().filter(|n| !new.contains(n)).cloned().collect(),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl Display for ListChanges {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Reloaded info.toml")?;
+        if !self.added.is_empty() {
+            write!(f, ", new exercises: {}", self.added.join(", "))?;
+        }
+        if !self.removed.is_empty() {
+            write!(f, ", removed exercises: {}", self.removed.join(", "))?;
+        }
+        write!(f, ".")?;
+        for warning in &self.warnings {
+            write!(f, " {}.", warning)?;
+        }
+        Ok(())
+    }
+}
+
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Clone, Default, Debug)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
-    // The path to the file containing the exercise's source code
+    // The path to the file containing the exercise's source code,
+    // or to the package directory of a Cargo exercise
     pub path: PathBuf,
-    // The mode of the exercise (Test, Compile, or Clippy)
+    // The mode of the exercise (Test, Compile, Clippy, or Cargo)
     pub mode: Mode,
     // The hint text associated with the exercise
+    #[serde(default)]
     pub hint: String,
+    // Hints that are revealed one at a time, from a gentle nudge to nearly
+    // the solution. Takes the place of `hint`.
+    #[serde(default)]
+    pub hints: Vec<String>,
+    // The reference solution, a file for single file exercises,
+    // or a directory shaped like the package for Cargo exercises
+    #[serde(default)]
+    pub solution: Option<PathBuf>,
+    // How many seconds the exercise may run before it is stopped
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    // What is piped to the standard input of a compile mode exercise
+    #[serde(default)]
+    pub input: Option<TextSource>,
+    // What a compile mode exercise has to print to pass
+    #[serde(default)]
+    pub expected_output: Option<TextSource>,
+    // Several inputs along with their expected outputs, each of which
+    // the exercise has to pass. Takes the place of `input` and `expected_output`.
+    #[serde(default)]
+    pub cases: Vec<Case>,
+    // Whether learners may change the tests of a test mode exercise,
+    // which are otherwise expected to stay the way they were
+    #[serde(default)]
+    pub editable_tests: bool,
+    // What the learner's code has to use, and what it must not use,
+    // for the exercise to count, e.g. `map` or `unsafe`
+    #[serde(default)]
+    pub require: Vec<Construct>,
+    #[serde(default)]
+    pub forbid: Vec<Construct>,
 }
 
 // An enum to track of the state of an Exercise.
@@ -78,13 +295,44 @@ pub struct ContextLine {
 // The result of compiling an exercise
 pub struct CompiledExercise<'a> {
     exercise: &'a Exercise,
+    // Whether the binary was taken from the cache instead of compiled
+    cached: bool,
     _handle: FileHandle,
 }
 
 impl<'a> CompiledExercise<'a> {
     // Run the compiled exercise
     pub fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        self.exercise.run()
+        self.run_binary(None, None)
+    }
+
+    // Run only the tests whose names contain the given filter
+    pub fn run_tests(&self, filter: &str) -> Result<ExerciseOutput, ExerciseOutput> {
+        self.run_binary(None, Some(filter))
+    }
+
+    // Run the compiled exercise with the input of the given case
+    pub fn run_case(&self, case: &Case) -> Result<ExerciseOutput, ExerciseOutput> {
+        let input = case
+            .input
+            .as_ref()
+            .map(TextSource::read)
+            .transpose()
+            .map_err(ExerciseOutput::unreadable)?;
+        self.run_binary(input, None)
+    }
+
+    // A binary from the cache that can't even be started is treated as if it
+    // wasn't there, and the exercise is compiled again to run it instead
+    fn run_binary(
+        &self,
+        input: Option<String>,
+        test_filter: Option<&str>,
+    ) -> Result<ExerciseOutput, ExerciseOutput> {
+        match self.exercise.run(input.clone(), test_filter) {
+            Err(_) if self.cached => self.exercise.build()?.run_binary(input, test_filter),
+            result => result.expect("Failed to run 'run' command"),
+        }
     }
 }
 
@@ -95,6 +343,26 @@ pub struct ExerciseOutput {
     pub stdout: String,
     // The textual contents of the standard error of the binary
     pub stderr: String,
+    // The diagnostics of the compiler, if the exercise failed to compile
+    pub diagnostics: Vec<Diagnostic>,
+    // Whether the binary was stopped for running too long
+    pub timed_out: bool,
+    // The text that was piped to the standard input of the binary
+    pub input: Option<String>,
+}
+
+impl ExerciseOutput {
+    // The output of a run that couldn't even start because a file it needs
+    // couldn't be read, with the reason in place of the standard error
+    pub fn unreadable(problem: String) -> ExerciseOutput {
+        ExerciseOutput {
+            stdout: String::new(),
+            stderr: problem,
+            diagnostics: Vec::new(),
+            timed_out: false,
+            input: None,
+        }
+    }
 }
 
 struct FileHandle;
@@ -107,16 +375,36 @@ impl Drop for FileHandle {
 
 impl Exercise {
     pub fn compile(&self) -> Result<CompiledExercise, ExerciseOutput> {
+        // Reuse the binary of an earlier successful compilation
+        // of the very same source without invoking rustc at all.
+        // Cargo keeps track of that for Cargo exercises itself.
+        if !self.is_cargo() {
+            if let Some(cached) = cache::lookup(self) {
+                if fs::copy(cached, temp_file()).is_ok() {
+                    return Ok(CompiledExercise {
+                        exercise: self,
+                        cached: true,
+                        _handle: FileHandle,
+                    });
+                }
+            }
+        }
+        self.build()
+    }
+
+    // Compile the exercise with rustc, Clippy or cargo, whatever its mode takes
+    fn build(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
         let cmd = match self.mode {
             Mode::Compile => Command::new("rustc")
                 .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
+                .args(RUSTC_JSON_ARGS)
                 .output(),
             Mode::Test => Command::new("rustc")
                 .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
+                .args(RUSTC_JSON_ARGS)
                 .output(),
             Mode::Clippy => {
+                let _guard = CLIPPY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
                 let cargo_toml = format!(
                     r#"[package]
 name = "{}"
@@ -124,8 +412,13 @@ version = "0.0.1"
 edition = "2018"
 [[bin]]
 name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
+path = '{}'"#,
+                    self.name,
+                    self.name,
+                    // Absolute, so that exercises outside of the clippy directory work too
+                    fs::canonicalize(&self.path)
+                        .unwrap_or_else(|_| self.path.clone())
+                        .display()
                 );
                 let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
                     "Failed to write Clippy Cargo.toml file."
@@ -154,98 +447,308 @@ path = "{}.rs""#,
                 Command::new("cargo")
                     .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
                     .args(RUSTC_COLOR_ARGS)
+                    .arg("--message-format=json-diagnostic-rendered-ansi")
                     .args(&["--", "-D", "warnings", "-D", "clippy::float_cmp"])
                     .output()
             }
+            Mode::Cargo => self
+                .cargo_command("test")
+                .args(["--no-run", "--message-format=json-diagnostic-rendered-ansi"])
+                .output(),
         }
         .expect("Failed to run 'compile' command.");
 
         if cmd.status.success() {
+            if !self.is_cargo() {
+                let _ignored = cache::store(self, Path::new(&temp_file()));
+            }
             Ok(CompiledExercise {
                 exercise: self,
+                cached: false,
                 _handle: FileHandle,
             })
         } else {
             clean();
+            // rustc writes its diagnostics to stderr, but cargo writes them
+            // to stdout. Either way, they end up in the rendered stderr.
+            let stdout = String::from_utf8_lossy(&cmd.stdout).to_string();
+            let (mut diagnostics, rendered) = diagnostics::parse(&stdout);
+            let (stderr_diagnostics, stderr) =
+                diagnostics::parse(&String::from_utf8_lossy(&cmd.stderr));
+            diagnostics.extend(stderr_diagnostics);
             Err(ExerciseOutput {
-                stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+                stdout,
+                stderr: rendered + &stderr,
+                diagnostics,
+                timed_out: false,
+                input: None,
             })
         }
     }
 
-    fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        let arg = match self.mode {
-            Mode::Test => "--show-output",
-            _ => "",
+    // Run the binary of the exercise, or fail if it can't even be started
+    fn run(
+        &self,
+        input: Option<String>,
+        test_filter: Option<&str>,
+    ) -> io::Result<Result<ExerciseOutput, ExerciseOutput>> {
+        let stdin = match input {
+            Some(_) => Stdio::piped(),
+            None => Stdio::null(),
+        };
+        let mut cmd = match self.mode {
+            Mode::Cargo => {
+                let mut cmd = self.cargo_command("test");
+                cmd.args(["--", "--show-output"]).args(test_filter);
+                cmd
+            }
+            Mode::Test => {
+                let mut cmd = Command::new(temp_file());
+                cmd.arg("--show-output").args(test_filter);
+                cmd
+            }
+            _ => Command::new(temp_file()),
         };
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
-            .output()
-            .expect("Failed to run 'run' command");
+        cmd.stdin(stdin)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Cargo itself needs more memory than exercise binaries may use,
+        // so only the CPU time of the tests it runs is limited then
+        #[cfg(target_os = "linux")]
+        limit_resources(&mut cmd, self.timeout(), !self.is_cargo());
+        // Its own process group lets a timeout stop the tests cargo started,
+        // which hold on to the pipes, along with cargo
+        #[cfg(target_os = "linux")]
+        std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+        let mut child = spawn(&mut cmd)?;
+
+        // Write the input while waiting too, in case it doesn't fit into the
+        // pipe. Closing stdin afterwards lets the binary see the end of it.
+        if let (Some(input), Some(mut stdin)) = (input.clone(), child.stdin.take()) {
+            thread::spawn(move || {
+                // The binary may exit without reading all of its input
+                let _ignored = stdin.write_all(input.as_bytes());
+            });
+        }
+        // Read the pipes while waiting, a chatty binary would block otherwise
+        let stdout = read_in_background(child.stdout.take());
+        let stderr = read_in_background(child.stderr.take());
+        let status = wait_with_timeout(&mut child, self.timeout());
 
         let output = ExerciseOutput {
-            stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+            stdout: String::from_utf8_lossy(&stdout.join().unwrap_or_default()).to_string(),
+            stderr: String::from_utf8_lossy(&stderr.join().unwrap_or_default()).to_string(),
+            diagnostics: Vec::new(),
+            timed_out: status.is_none_or(|status| exceeded_cpu_limit(&status)),
+            input,
         };
 
-        if cmd.status.success() {
-            Ok(output)
-        } else {
-            Err(output)
+        match status {
+            Some(status) if status.success() => Ok(Ok(output)),
+            _ => Ok(Err(output)),
         }
     }
 
-    pub fn state(&self) -> State {
-        let mut source_file =
-            File::open(&self.path).expect("We were unable to open the exercise file!");
-
-        let source = {
-            let mut s = String::new();
-            source_file
-                .read_to_string(&mut s)
-                .expect("We were unable to read the exercise file!");
-            s
-        };
+    // The topic of the exercise, i.e. the directory it is in
+    pub fn topic(&self) -> String {
+        self.path
+            .parent()
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
 
-        let re = Regex::new(I_AM_DONE_REGEX).unwrap();
+    pub fn is_cargo(&self) -> bool {
+        matches!(self.mode, Mode::Cargo)
+    }
+
+    // The Cargo.toml of a Cargo exercise
+    pub fn manifest_path(&self) -> PathBuf {
+        self.path.join("Cargo.toml")
+    }
+
+    // A cargo command for the package of a Cargo exercise.
+    // The packages share one target directory, so that their dependencies
+    // are only built once. `--offline` makes cargo use the crates that were
+    // vendored for the course, see `.cargo/config.toml` next to info.toml.
+    fn cargo_command(&self, subcommand: &str) -> Command {
+        let mut cmd = Command::new("cargo");
+        cmd.arg(subcommand)
+            .arg("--offline")
+            .arg("--manifest-path")
+            .arg(self.manifest_path())
+            .env("CARGO_TARGET_DIR", cache::cargo_target_dir());
+        cmd
+    }
+
+    // The files the learner edits to solve the exercise: the exercise file
+    // itself, or the sources and Cargo.toml of a Cargo exercise
+    pub fn source_files(&self) -> Vec<PathBuf> {
+        if !self.is_cargo() {
+            return vec![self.path.clone()];
+        }
+        let pattern = self.path.join("**").join("*.rs");
+        let mut files: Vec<PathBuf> = glob(&pattern.to_string_lossy())
+            .expect("The exercise path is not a valid pattern")
+            .filter_map(Result::ok)
+            .filter(|file| !file.components().any(|c| c.as_os_str() == "target"))
+            .collect();
+        files.push(self.manifest_path());
+        files
+    }
+
+    // The files the exercise reads its input or expected output from
+    pub fn text_files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for case in self.cases() {
+            for source in [case.input, case.expected_output].into_iter().flatten() {
+                if let TextSource::File { file } = source {
+                    files.push(file);
+                }
+            }
+        }
+        files
+    }
+
+    // Whether the given file belongs to this exercise
+    pub fn contains(&self, file: &Path) -> bool {
+        self.source_files()
+            .iter()
+            .any(|source| file.ends_with(source))
+    }
 
-        if !re.is_match(&source) {
-            return State::Done;
+    // How long the exercise may run before it is considered stuck
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS))
+    }
+
+    // The runs a compile mode exercise has to pass: either its `cases`,
+    // or a single one made of its own `input` and `expected_output`
+    pub fn cases(&self) -> Vec<Case> {
+        if !self.cases.is_empty() {
+            return self.cases.clone();
         }
+        vec![Case {
+            input: self.input.clone(),
+            expected_output: self.expected_output.clone(),
+        }]
+    }
 
-        let matched_line_index = source
-            .lines()
-            .enumerate()
-            .find_map(|(i, line)| if re.is_match(line) { Some(i) } else { None })
-            .expect("This should not happen at all");
-
-        let min_line = ((matched_line_index as i32) - (CONTEXT as i32)).max(0) as usize;
-        let max_line = matched_line_index + CONTEXT;
-
-        let context = source
-            .lines()
-            .enumerate()
-            .filter(|&(i, _)| i >= min_line && i <= max_line)
-            .map(|(i, line)| ContextLine {
-                line: line.to_string(),
-                number: i + 1,
-                important: i == matched_line_index,
+    // The files of the learner paired with their counterparts in the solution
+    pub fn solution_files(&self) -> Vec<(PathBuf, PathBuf)> {
+        let Some(solution) = &self.solution else {
+            return Vec::new();
+        };
+        if !self.is_cargo() {
+            return vec![(self.path.clone(), solution.clone())];
+        }
+        self.source_files()
+            .into_iter()
+            .filter_map(|file| {
+                let counterpart = solution.join(file.strip_prefix(&self.path).ok()?);
+                counterpart.exists().then_some((file, counterpart))
             })
-            .collect();
+            .collect()
+    }
 
-        State::Pending(context)
+    // The hints in the order they are revealed: either its `hints`,
+    // or its single `hint`, if it has one
+    pub fn hints(&self) -> Vec<&str> {
+        if !self.hints.is_empty() {
+            return self.hints.iter().map(String::as_str).collect();
+        }
+        if self.hint.is_empty() {
+            Vec::new()
+        } else {
+            vec![self.hint.as_str()]
+        }
     }
 
+    // A Cargo exercise is pending as long as any of its files
+    // still has the "I AM NOT DONE" comment
+    pub fn state(&self) -> State {
+        for path in self.source_files() {
+            if let State::Pending(context) = file_state(&path) {
+                return State::Pending(context);
+            }
+        }
+        State::Done
+    }
     // Check that the exercise looks to be solved using self.state()
     // This is not the best way to check since
     // the user can just remove the "I AM NOT DONE" string from the file
     // without actually having solved anything.
-    // The only other way to truly check this would to compile and run
-    // the exercise; which would be both costly and counterintuitive
+    // Use ProgressStore::is_done to also require a verified run.
     pub fn looks_done(&self) -> bool {
         self.state() == State::Done
     }
+
+    // Hash of the exercise's source code.
+    // The "I AM NOT DONE" comment and blank lines are left out, so that
+    // removing the comment doesn't invalidate a run that was verified before.
+    pub fn source_hash(&self) -> String {
+        let re = Regex::new(I_AM_DONE_REGEX).unwrap();
+
+        let mut hasher = Sha256::new();
+        for path in self.source_files() {
+            let source =
+                fs::read_to_string(&path).expect("We were unable to read the exercise file!");
+            // Cargo exercises can have several files, which may be renamed
+            if self.is_cargo() {
+                hasher.update(path.to_string_lossy().as_bytes());
+                hasher.update(b"\n");
+            }
+            for line in source
+                .lines()
+                .filter(|line| !line.trim().is_empty() && !re.is_match(line))
+            {
+                hasher.update(line.as_bytes());
+                hasher.update(b"\n");
+            }
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+// Whether a single source file still has the "I AM NOT DONE" comment
+fn file_state(path: &Path) -> State {
+    let mut source_file = File::open(path).expect("We were unable to open the exercise file!");
+
+    let source = {
+        let mut s = String::new();
+        source_file
+            .read_to_string(&mut s)
+            .expect("We were unable to read the exercise file!");
+        s
+    };
+
+    let re = Regex::new(I_AM_DONE_REGEX).unwrap();
+
+    if !re.is_match(&source) {
+        return State::Done;
+    }
+
+    let matched_line_index = source
+        .lines()
+        .enumerate()
+        .find_map(|(i, line)| if re.is_match(line) { Some(i) } else { None })
+        .expect("This should not happen at all");
+
+    let min_line = ((matched_line_index as i32) - (CONTEXT as i32)).max(0) as usize;
+    let max_line = matched_line_index + CONTEXT;
+
+    let context = source
+        .lines()
+        .enumerate()
+        .filter(|&(i, _)| i >= min_line && i <= max_line)
+        .map(|(i, line)| ContextLine {
+            line: line.to_string(),
+            number: i + 1,
+            important: i == matched_line_index,
+        })
+        .collect();
+
+    State::Pending(context)
 }
 
 impl Display for Exercise {
@@ -254,6 +757,113 @@ impl Display for Exercise {
     }
 }
 
+// Ignore differences in line endings and trailing whitespace,
+// which are invisible when looking at the output
+pub fn normalize_output(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().map(str::trim_end).collect();
+    let mut normalized = lines.join("\n");
+    normalized.truncate(normalized.trim_end().len());
+    normalized
+}
+
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ignored = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+// Start the command, waiting for its binary to become executable. While
+// one worker thread writes the binary, another one may start a process,
+// which keeps the binary open for writing until it executes itself.
+fn spawn(cmd: &mut Command) -> io::Result<Child> {
+    let mut attempts = 0;
+    loop {
+        match cmd.spawn() {
+            Err(e) if e.kind() == io::ErrorKind::ExecutableFileBusy && attempts < 100 => {
+                attempts += 1;
+                thread::sleep(Duration::from_millis(10));
+            }
+            result => return result,
+        }
+    }
+}
+
+// Wait for the child to exit, killing it once the timeout has expired.
+// Returns None if the child had to be killed.
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> Option<ExitStatus> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Some(status),
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+            _ => {
+                kill(child);
+                let _ignored = child.wait();
+                return None;
+            }
+        }
+    }
+}
+
+// Kill the child and everything it started in its process group
+#[cfg(target_os = "linux")]
+fn kill(child: &mut Child) {
+    // SAFETY: kill only sends a signal, a negative pid sends it to the group
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn kill(child: &mut Child) {
+    let _ignored = child.kill();
+}
+
+// Limit the CPU time and memory of the exercise binary, so that
+// a runaway exercise can't take down the learner's machine
+#[cfg(target_os = "linux")]
+fn limit_resources(cmd: &mut Command, timeout: Duration, limit_memory: bool) {
+    use std::os::unix::process::CommandExt;
+
+    let cpu_secs = timeout.as_secs() + 1;
+    let cpu = libc::rlimit {
+        rlim_cur: cpu_secs,
+        rlim_max: cpu_secs + 1,
+    };
+    let memory = libc::rlimit {
+        rlim_cur: MEMORY_LIMIT_BYTES,
+        rlim_max: MEMORY_LIMIT_BYTES,
+    };
+    // SAFETY: setrlimit is async-signal-safe, so it may be called
+    // between fork and exec
+    unsafe {
+        cmd.pre_exec(move || {
+            if libc::setrlimit(libc::RLIMIT_CPU, &cpu) != 0
+                || (limit_memory && libc::setrlimit(libc::RLIMIT_AS, &memory) != 0)
+            {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+// The kernel stops processes that use up their CPU time limit with SIGXCPU
+#[cfg(target_os = "linux")]
+fn exceeded_cpu_limit(status: &ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal() == Some(libc::SIGXCPU)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn exceeded_cpu_limit(_status: &ExitStatus) -> bool {
+    false
+}
+
 #[inline]
 fn clean() {
     let _ignored = remove_file(&temp_file());
@@ -264,6 +874,27 @@ mod test {
     use super::*;
     use std::path::Path;
 
+    #[test]
+    fn test_roots_of_the_exercises() {
+        let exercise = |path: &str, mode: Mode| Exercise {
+            path: PathBuf::from(path),
+            mode,
+            ..Default::default()
+        };
+        let exercises = vec![
+            exercise("exercises/intro/intro1.rs", Mode::Compile),
+            exercise("./exercises/if/if1.rs", Mode::Compile),
+            exercise("quiz.rs", Mode::Test),
+            exercise("../shared/lesson.rs", Mode::Compile),
+            exercise("adder", Mode::Cargo),
+        ];
+        let roots: Vec<PathBuf> = roots(&exercises).into_iter().collect();
+        assert_eq!(
+            roots,
+            ["../shared", "adder", "exercises", "quiz.rs"].map(PathBuf::from)
+        );
+    }
+
     #[test]
     fn test_clean() {
         File::create(&temp_file()).unwrap();
@@ -272,6 +903,7 @@ mod test {
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
             mode: Mode::Compile,
             hint: String::from(""),
+            ..Default::default()
         };
         let compiled = exercise.compile().unwrap();
         drop(compiled);
@@ -284,7 +916,7 @@ mod test {
             name: "pending_exercise".into(),
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::new(),
+            ..Default::default()
         };
 
         let state = exercise.state();
@@ -325,21 +957,284 @@ mod test {
             name: "finished_exercise".into(),
             path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::new(),
+            ..Default::default()
         };
 
         assert_eq!(exercise.state(), State::Done);
     }
 
+    #[test]
+    fn test_source_hash_ignores_marker() {
+        let pending = Exercise {
+            name: "pending_exercise".into(),
+            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let finished = Exercise {
+            name: "finished_exercise".into(),
+            path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+
+        assert_eq!(pending.source_hash(), finished.source_hash());
+    }
+
+    #[test]
+    fn test_exercise_that_runs_too_long() {
+        let exercise = Exercise {
+            name: "infinite_loop".into(),
+            path: PathBuf::from("tests/fixture/timeout/infinite_loop.rs"),
+            mode: Mode::Compile,
+            timeout: Some(1),
+            ..Default::default()
+        };
+        let out = exercise.compile().unwrap().run().unwrap_err();
+        assert!(out.timed_out);
+        assert!(out.stdout.contains("Looping forever"));
+    }
+
+    #[test]
+    fn test_expected_output_inline_or_from_file() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+            [[exercises]]
+            name = "inline"
+            path = "inline.rs"
+            mode = "compile"
+            hint = ""
+            expected_output = "Hello"
+
+            [[exercises]]
+            name = "from_file"
+            path = "from_file.rs"
+            mode = "compile"
+            hint = ""
+            expected_output = { file = "hello.txt" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            list.exercises[0].expected_output,
+            Some(TextSource::Inline("Hello".into()))
+        );
+        assert_eq!(
+            list.exercises[1].expected_output,
+            Some(TextSource::File {
+                file: PathBuf::from("hello.txt")
+            })
+        );
+    }
+
+    #[test]
+    fn test_hints_take_the_place_of_hint() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+            [[exercises]]
+            name = "single"
+            path = "single.rs"
+            mode = "compile"
+            hint = "Just one"
+
+            [[exercises]]
+            name = "leveled"
+            path = "leveled.rs"
+            mode = "compile"
+            hints = ["First", "Second"]
+
+            [[exercises]]
+            name = "none"
+            path = "none.rs"
+            mode = "compile"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(list.exercises[0].hints(), vec!["Just one"]);
+        assert_eq!(list.exercises[1].hints(), vec!["First", "Second"]);
+        assert!(list.exercises[2].hints().is_empty());
+    }
+
+    #[test]
+    fn test_check_output_ignores_trailing_whitespace() {
+        let case = Case {
+            input: None,
+            expected_output: Some(TextSource::Inline("a\n b\n".into())),
+        };
+
+        assert_eq!(case.check_output("a  \r\n b\n\n"), Ok(()));
+        assert_eq!(case.check_output("a\nb\n"), Err("a\n b".into()));
+    }
+
+    #[test]
+    fn test_cases_take_the_place_of_input() {
+        let list: ExerciseList = toml::from_str(
+            r#"
+            [[exercises]]
+            name = "single"
+            path = "single.rs"
+            mode = "compile"
+            hint = ""
+            input = "1 2"
+            expected_output = "3"
+
+            [[exercises]]
+            name = "judge"
+            path = "judge.rs"
+            mode = "compile"
+            hint = ""
+
+            [[exercises.cases]]
+            input = "1 2"
+            expected_output = "3"
+
+            [[exercises.cases]]
+            input = { file = "large.txt" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            list.exercises[0].cases(),
+            vec![Case {
+                input: Some(TextSource::Inline("1 2".into())),
+                expected_output: Some(TextSource::Inline("3".into())),
+            }]
+        );
+        assert_eq!(list.exercises[1].cases(), list.exercises[1].cases);
+        assert_eq!(list.exercises[1].cases.len(), 2);
+        assert_eq!(list.exercises[1].cases[1].expected_output, None);
+    }
+
+    #[test]
+    fn test_input_is_piped_to_stdin() {
+        let exercise = Exercise {
+            name: "sum".into(),
+            path: PathBuf::from("tests/fixture/input/sum.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let case = Case {
+            input: Some(TextSource::Inline("1 2\n3 4\n".into())),
+            expected_output: None,
+        };
+        let out = exercise.compile().unwrap().run_case(&case).unwrap();
+        assert_eq!(out.stdout, "3\n7\n");
+        assert_eq!(out.input.as_deref(), Some("1 2\n3 4\n"));
+    }
+
+    #[test]
+    fn test_missing_input_file_fails_the_run() {
+        let exercise = Exercise {
+            name: "sum".into(),
+            path: PathBuf::from("tests/fixture/input/sum.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let case = Case {
+            input: Some(TextSource::File {
+                file: PathBuf::from("tests/fixture/input/missing.txt"),
+            }),
+            expected_output: None,
+        };
+        assert!(case.load().is_err());
+        let out = exercise.compile().unwrap().run_case(&case).unwrap_err();
+        assert!(out
+            .stderr
+            .starts_with("Could not read tests/fixture/input/missing.txt"));
+    }
+
+    #[test]
+    fn test_broken_cached_binary_is_compiled_again() {
+        let exercise = Exercise {
+            name: "cache_broken".into(),
+            path: PathBuf::from("tests/fixture/success/compSuccess.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        // Not even executable, so it can't be started
+        cache::store(&exercise, Path::new("tests/fixture/success/info.toml")).unwrap();
+
+        assert!(exercise.compile().unwrap().run().is_ok());
+        let cached = cache::lookup(&exercise).unwrap();
+        assert_ne!(
+            fs::read(cached).unwrap(),
+            fs::read("tests/fixture/success/info.toml").unwrap()
+        );
+        fs::remove_dir_all(Path::new(".rustlings-cache").join("cache_broken")).unwrap();
+    }
+
+    #[test]
+    fn test_cargo_exercise_spans_its_package() {
+        let exercise = Exercise {
+            name: "broken".into(),
+            path: PathBuf::from("tests/fixture/cargo/broken"),
+            mode: Mode::Cargo,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            exercise.source_files(),
+            vec![
+                PathBuf::from("tests/fixture/cargo/broken/src/lib.rs"),
+                PathBuf::from("tests/fixture/cargo/broken/Cargo.toml"),
+            ]
+        );
+        assert!(exercise.contains(
+            &env::current_dir()
+                .unwrap()
+                .join("tests/fixture/cargo/broken/src/lib.rs")
+        ));
+        assert!(!exercise.looks_done());
+    }
+
     #[test]
     fn test_exercise_with_output() {
         let exercise = Exercise {
             name: "exercise_with_output".into(),
             path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
             mode: Mode::Test,
-            hint: String::new(),
+            ..Default::default()
         };
         let out = exercise.compile().unwrap().run().unwrap();
         assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
     }
+
+    #[test]
+    fn test_load_validates_exercise_list() {
+        // The paths are relative to the fixture, so none of them exist here
+        let error = ExerciseList::load(Path::new("tests/fixture/state/info.toml")).unwrap_err();
+        for line in [3, 9, 15] {
+            assert!(error.contains(&format!("info.toml:{}: error: The exercise", line)));
+        }
+    }
+
+    #[test]
+    fn test_list_changes() {
+        let exercise = |name: &str| Exercise {
+            name: name.into(),
+            path: PathBuf::from(format!("{}.rs", name)),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let old = vec![exercise("intro1"), exercise("intro2")];
+        let new = vec![exercise("intro1"), exercise("intro3"), exercise("intro4")];
+
+        let changes = ListChanges::between(&old, &new);
+        assert_eq!(changes.added, vec!["intro3", "intro4"]);
+        assert_eq!(changes.removed, vec!["intro2"]);
+        assert_eq!(
+            changes.to_string(),
+            "Reloaded info.toml, new exercises: intro3, intro4, removed exercises: intro2."
+        );
+
+        let mut changes = ListChanges::between(&old, &old);
+        changes.warnings.push("Failed to record the tests".into());
+        assert_eq!(
+            changes.to_string(),
+            "Reloaded info.toml. Failed to record the tests."
+        );
+    }
 }
@@ -0,0 +1,181 @@
+use crate::cache;
+use crate::exercise::Exercise;
+use crate::tamper;
+use crate::verify::{evaluate, judge, Verdict};
+use indicatif::ProgressBar;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// How a student did on an exercise
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Grade {
+    Verified(Verdict),
+    // The exercise isn't in the student's checkout
+    Missing,
+}
+
+impl Grade {
+    fn name(&self) -> &'static str {
+        match self {
+            Grade::Verified(verdict) => verdict.name(),
+            Grade::Missing => "missing",
+        }
+    }
+}
+
+// Verify the exercises of every student checkout, and write how each of
+// them did to the gradebook, a CSV file with a line per student.
+// The exercises are the ones of this course: the student's info.toml,
+// and anything in the checkout besides the exercises, is ignored.
+// Meant for instructors, the progress of this course is left alone.
+pub fn grade(exercises: &[Exercise], students: &[PathBuf], gradebook: &Path) -> Result<(), ()> {
+    let mut grades = Vec::new();
+    for student in students {
+        if !student.is_dir() {
+            warn!("{} is not a directory", student.display());
+            return Err(());
+        }
+        let student_grades = match grade_student(exercises, student) {
+            Ok(student_grades) => student_grades,
+            Err(e) => {
+                warn!("Could not grade {}", student.display());
+                println!("{}", e);
+                return Err(());
+            }
+        };
+        let done = count_done(&student_grades);
+        println!(
+            "{}: {} of {} exercises are done.",
+            student.display(),
+            done,
+            exercises.len()
+        );
+        grades.push((student, student_grades));
+    }
+
+    let mut csv = String::from("student,done,total");
+    for exercise in exercises {
+        csv.push(',');
+        csv.push_str(&csv_field(&exercise.name));
+    }
+    csv.push('\n');
+    for (student, student_grades) in grades {
+        csv.push_str(&format!(
+            "{},{},{}",
+            csv_field(&student.display().to_string()),
+            count_done(&student_grades),
+            exercises.len()
+        ));
+        for grade in student_grades {
+            csv.push(',');
+            csv.push_str(grade.name());
+        }
+        csv.push('\n');
+    }
+    if let Err(e) = fs::write(gradebook, csv) {
+        warn!("Could not write the gradebook to {}", gradebook.display());
+        println!("{}", e);
+        return Err(());
+    }
+    success!("Wrote the gradebook to {}", gradebook.display());
+    Ok(())
+}
+
+fn count_done(grades: &[Grade]) -> usize {
+    grades
+        .iter()
+        .filter(|grade| **grade == Grade::Verified(Verdict::Done))
+        .count()
+}
+
+// Quote the field if it would otherwise be read as more than one
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// Grade the exercises of a student in a directory of its own, where the
+// student's exercises are copied to. Compiling and running them there keeps
+// their temporary files and the Clippy Cargo.toml apart from the course's
+// and the other students'. The files the exercises read their input and
+// expected output from are copied from the course, so they can't be changed.
+fn grade_student(exercises: &[Exercise], student: &Path) -> io::Result<Vec<Grade>> {
+    let name = student
+        .canonicalize()?
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let dir = cache::grade_dir().join(name);
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::create_dir_all(&dir)?;
+
+    let mut grades = Vec::new();
+    for exercise in exercises {
+        let theirs = Exercise {
+            path: student.join(&exercise.path),
+            ..exercise.clone()
+        };
+        let changes = tampering(exercise, &theirs);
+        let grade = if !theirs.path.exists() {
+            Grade::Missing
+        } else if !changes.is_empty() {
+            warn!("The tests of {} were changed:", theirs);
+            for change in changes {
+                println!("- {}", change);
+            }
+            Grade::Verified(Verdict::Tampered)
+        } else {
+            for file in theirs.source_files() {
+                let relative = file.strip_prefix(student).unwrap_or(&file);
+                copy(&file, &dir.join(relative))?;
+            }
+            for file in exercise.text_files() {
+                copy(&file, &dir.join(&file))?;
+            }
+            Grade::Verified(Verdict::NotRun)
+        };
+        grades.push(grade);
+    }
+
+    let course = env::current_dir()?;
+    env::set_current_dir(&dir)?;
+    for (exercise, grade) in exercises.iter().zip(grades.iter_mut()) {
+        if *grade != Grade::Verified(Verdict::NotRun) {
+            continue;
+        }
+        let progress_bar = ProgressBar::new_spinner();
+        progress_bar.set_message(format!("Grading {} of {}...", exercise, student.display()));
+        progress_bar.enable_steady_tick(100);
+        let outcome = evaluate(exercise, &progress_bar);
+        progress_bar.finish_and_clear();
+        *grade = Grade::Verified(judge(exercise, &outcome));
+    }
+    env::set_current_dir(course)?;
+    Ok(grades)
+}
+
+fn copy(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(from, to)?;
+    Ok(())
+}
+
+// How the student changed the tests of the exercise. They are compared
+// with the tests of the course, not with the student's original copy.
+fn tampering(exercise: &Exercise, theirs: &Exercise) -> Vec<String> {
+    if !tamper::is_protected(exercise) {
+        return Vec::new();
+    }
+    let ours = fs::read_to_string(&exercise.path).unwrap_or_default();
+    let theirs = fs::read_to_string(&theirs.path).unwrap_or_default();
+    tamper::compare(&ours, &theirs)
+}
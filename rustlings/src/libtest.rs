@@ -0,0 +1,219 @@
+use console::style;
+use std::collections::HashMap;
+
+// How a single #[test] function did
+#[derive(PartialEq, Debug)]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+// The result of a single #[test] function, as reported by the test harness
+#[derive(PartialEq, Debug)]
+pub struct TestResult {
+    // The path of the test function, e.g. "tests::it_works"
+    pub name: String,
+    pub status: TestStatus,
+    // Why a failed test failed, e.g. the message it panicked with
+    pub message: Option<String>,
+}
+
+// Parse the human readable output of a libtest harness.
+// `--format json` would be easier to parse, but is only available on nightly.
+pub fn parse(stdout: &str) -> Vec<TestResult> {
+    let mut results = Vec::new();
+    for line in stdout.lines() {
+        // e.g. "test tests::it_works ... ok"
+        let Some((name, status)) = line
+            .strip_prefix("test ")
+            .and_then(|line| line.split_once(" ... "))
+        else {
+            continue;
+        };
+        let status = match status {
+            "ok" => TestStatus::Passed,
+            "FAILED" => TestStatus::Failed,
+            status if status.starts_with("ignored") => TestStatus::Ignored,
+            _ => continue,
+        };
+        results.push(TestResult {
+            name: name.to_string(),
+            status,
+            message: None,
+        });
+    }
+
+    let outputs = test_outputs(stdout);
+    for result in &mut results {
+        if result.status == TestStatus::Failed {
+            result.message = outputs.get(result.name.as_str()).and_then(|o| failure(o));
+        }
+    }
+    results
+}
+
+// The output the harness shows for every test in a section of its own,
+// e.g. "---- tests::it_works stdout ----". With `--show-output`,
+// tests may have two sections, the one listed under "failures:" comes last.
+fn test_outputs(stdout: &str) -> HashMap<&str, Vec<&str>> {
+    let mut outputs: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut current = None;
+    for line in stdout.lines() {
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|line| line.strip_suffix(" stdout ----"))
+        {
+            outputs.insert(name, Vec::new());
+            current = Some(name);
+        } else if line == "successes:" || line == "failures:" {
+            current = None;
+        } else if let Some(name) = current {
+            outputs.entry(name).or_default().push(line);
+        }
+    }
+    outputs
+}
+
+// Find out why a test failed from its output: the message it panicked with,
+// the error it returned, or whatever else the harness had to say
+fn failure(output: &[&str]) -> Option<String> {
+    if let Some(i) = output.iter().position(|line| line.contains("panicked at")) {
+        // Before Rust 1.73: thread 'x' panicked at 'message', src/lib.rs:1:1
+        if let Some((_, rest)) = output[i].split_once("panicked at '") {
+            return rest
+                .rsplit_once("', ")
+                .map(|(message, _)| message.to_string());
+        }
+        // Since then, the message is on the lines following the location
+        let message: Vec<&str> = output[i + 1..]
+            .iter()
+            .take_while(|line| {
+                !line.is_empty()
+                    && !line.starts_with("note:")
+                    && !line.starts_with("stack backtrace:")
+            })
+            .copied()
+            .collect();
+        return Some(message.join("\n"));
+    }
+    output
+        .iter()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line.to_string())
+}
+
+// Show which tests passed and which failed, and why
+pub fn print_checklist(results: &[TestResult]) {
+    print!("{}", checklist(results));
+}
+
+// A line for every test, marking whether it passed, followed by a summary
+pub fn checklist(results: &[TestResult]) -> String {
+    let mut text = String::new();
+    for result in results {
+        text += &match result.status {
+            TestStatus::Passed => format!("  {} {}\n", style("✓").green(), result.name),
+            TestStatus::Ignored => format!("  {} {} (ignored)\n", style("-").dim(), result.name),
+            TestStatus::Failed => format!("  {} {}\n", style("✗").red(), style(&result.name).red()),
+        };
+        for line in result.message.iter().flat_map(|message| message.lines()) {
+            text += &format!("      {}\n", line);
+        }
+    }
+    let passed = results
+        .iter()
+        .filter(|r| r.status == TestStatus::Passed)
+        .count();
+    let counted = results
+        .iter()
+        .filter(|r| r.status != TestStatus::Ignored)
+        .count();
+    text += &format!("{} of {} tests pass.\n", passed, counted);
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const OUTPUT: &str = "
+running 4 tests
+test tests::fails ... FAILED
+test tests::ignored ... ignored, not yet
+test tests::passes ... ok
+test tests::returns_err ... FAILED
+
+successes:
+
+---- tests::passes stdout ----
+Hello
+
+successes:
+    tests::passes
+
+failures:
+
+---- tests::fails stdout ----
+Hello
+thread 'tests::fails' (1234) panicked at src/lib.rs:8:5:
+assertion `left == right` failed
+  left: 2
+ right: 3
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+---- tests::returns_err stdout ----
+Error: \"not a number\"
+
+
+failures:
+    tests::fails
+    tests::returns_err
+
+test result: FAILED. 1 passed; 2 failed; 1 ignored; 0 measured; 0 filtered out
+";
+
+    #[test]
+    fn test_parse_results_and_failures() {
+        let results = parse(OUTPUT);
+
+        let statuses: Vec<(&str, &TestStatus)> = results
+            .iter()
+            .map(|r| (r.name.as_str(), &r.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("tests::fails", &TestStatus::Failed),
+                ("tests::ignored", &TestStatus::Ignored),
+                ("tests::passes", &TestStatus::Passed),
+                ("tests::returns_err", &TestStatus::Failed),
+            ]
+        );
+        assert_eq!(
+            results[0].message.as_deref(),
+            Some("assertion `left == right` failed\n  left: 2\n right: 3")
+        );
+        assert_eq!(results[2].message, None);
+        assert_eq!(
+            results[3].message.as_deref(),
+            Some("Error: \"not a number\"")
+        );
+    }
+
+    #[test]
+    fn test_parse_old_panic_message() {
+        let output = "
+test it_works ... FAILED
+
+failures:
+
+---- it_works stdout ----
+thread 'it_works' panicked at 'Something went wrong', src/lib.rs:3:5
+";
+        assert_eq!(
+            parse(output)[0].message.as_deref(),
+            Some("Something went wrong")
+        );
+    }
+}
@@ -0,0 +1,494 @@
+use crate::exercise::{self, Exercise, ExerciseList, Mode};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(PartialEq, Debug)]
+pub enum Severity {
+    // The course can't be used like this
+    Error,
+    // Most likely a mistake, but rustlings works anyway
+    Warning,
+}
+
+// Something wrong with info.toml or the exercises it lists
+#[derive(PartialEq, Debug)]
+pub struct Problem {
+    pub severity: Severity,
+    // The line of info.toml the problem is at, if it is at one
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Problem {
+    // The problem the way compilers report them, e.g.
+    // `info.toml:12: error: The exercise ...`
+    pub fn describe(&self, file: &Path) -> String {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match self.line {
+            Some(line) => format!(
+                "{}:{}: {}: {}",
+                file.display(),
+                line,
+                severity,
+                self.message
+            ),
+            None => format!("{}: {}: {}", file.display(), severity, self.message),
+        }
+    }
+}
+
+// Print every problem of the course described by the given info.toml.
+// Only fails if one of them keeps the course from being used.
+pub fn lint_course(path: &Path) -> Result<(), ()> {
+    let toml_str = match fs::read_to_string(path) {
+        Ok(toml_str) => toml_str,
+        Err(e) => {
+            warn!("Could not read {}", path.display());
+            println!("{}", e);
+            return Err(());
+        }
+    };
+    let problems = lint(&toml_str);
+    if problems.is_empty() {
+        success!("{} looks good", path.display());
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("{}", problem.describe(path));
+    }
+    let errors = problems
+        .iter()
+        .filter(|p| p.severity == Severity::Error)
+        .count();
+    println!("{} errors, {} warnings", errors, problems.len() - errors);
+    if errors > 0 {
+        return Err(());
+    }
+    Ok(())
+}
+
+// Parse info.toml, failing with every problem that keeps the course from
+// being used, e.g. names used twice or files that don't exist
+pub fn parse(toml_str: &str) -> Result<Vec<Exercise>, Vec<Problem>> {
+    let exercises = deserialize(toml_str).map_err(|problem| vec![problem])?;
+    let problems = errors(toml_str, &exercises);
+    if problems.is_empty() {
+        Ok(exercises)
+    } else {
+        Err(problems)
+    }
+}
+
+// The exercises of info.toml, or the syntax error that keeps them from
+// being read at all
+fn deserialize(toml_str: &str) -> Result<Vec<Exercise>, Problem> {
+    toml::from_str::<ExerciseList>(toml_str)
+        .map(|list| list.exercises)
+        .map_err(|e| Problem {
+            severity: Severity::Error,
+            line: e.line_col().map(|(line, _)| line + 1),
+            message: e.to_string(),
+        })
+}
+
+// Every problem of the course, including the ones that are likely mistakes
+// but don't keep it from being used
+pub fn lint(toml_str: &str) -> Vec<Problem> {
+    let exercises = match deserialize(toml_str) {
+        Ok(exercises) => exercises,
+        Err(problem) => return vec![problem],
+    };
+    let lines = Lines::new(toml_str);
+    let main_regex = Regex::new(r"\bfn\s+main\s*\(").unwrap();
+    let mut problems = errors(toml_str, &exercises);
+    for (i, exercise) in exercises.iter().enumerate() {
+        let mut warn = |key: &str, message: String| {
+            problems.push(Problem {
+                severity: Severity::Warning,
+                line: lines.of(i, key),
+                message,
+            })
+        };
+        if exercise.hints().is_empty() {
+            warn("name", format!("{} has no hint", exercise.name));
+        }
+        if exercise.is_cargo() {
+            continue;
+        }
+        // Exercises that don't exist are errors already
+        let Ok(source) = fs::read_to_string(&exercise.path) else {
+            continue;
+        };
+        let has_tests = source.contains("#[test]");
+        let has_main = main_regex.is_match(&source);
+        match exercise.mode {
+            Mode::Test if !has_tests => warn(
+                "mode",
+                format!("{} is in test mode, but has no #[test]", exercise.name),
+            ),
+            Mode::Compile | Mode::Clippy if !has_main => warn(
+                "mode",
+                format!(
+                    "{} is in {} mode, but has no main function",
+                    exercise.name,
+                    format!("{:?}", exercise.mode).to_lowercase()
+                ),
+            ),
+            _ => {}
+        }
+    }
+
+    for message in unlisted_files(&exercises).into_values() {
+        problems.push(Problem {
+            severity: Severity::Warning,
+            line: None,
+            message,
+        });
+    }
+    problems
+}
+
+fn errors(toml_str: &str, exercises: &[Exercise]) -> Vec<Problem> {
+    let lines = Lines::new(toml_str);
+    let mut problems = Vec::new();
+    if exercises.is_empty() {
+        problems.push(Problem {
+            severity: Severity::Error,
+            line: None,
+            message: "There are no exercises".to_string(),
+        });
+    }
+
+    let mut names: HashMap<&str, usize> = HashMap::new();
+    for (i, exercise) in exercises.iter().enumerate() {
+        let mut error = |key: &str, message: String| {
+            problems.push(Problem {
+                severity: Severity::Error,
+                line: lines.of(i, key),
+                message,
+            })
+        };
+        if let Some(&first) = names.get(exercise.name.as_str()) {
+            let also = match lines.of(first, "name") {
+                Some(line) => format!(" (see line {})", line),
+                None => String::new(),
+            };
+            error(
+                "name",
+                format!("More than one exercise is called {}{}", exercise.name, also),
+            );
+        }
+        names.entry(&exercise.name).or_insert(i);
+
+        // The copies kept for `rustlings reset` mirror the paths of the
+        // exercises, which mustn't lead them outside of the course
+        let outside = exercise
+            .path
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+        if outside {
+            error(
+                "path",
+                format!(
+                    "The exercise {} points to {}, but paths have to be relative to the course, without `..`",
+                    exercise.name,
+                    exercise.path.display()
+                ),
+            );
+        } else if !exercise.path.exists() {
+            error(
+                "path",
+                format!(
+                    "The exercise {} points to {}, which doesn't exist",
+                    exercise.name,
+                    exercise.path.display()
+                ),
+            );
+        } else if exercise.is_cargo() && !exercise.manifest_path().exists() {
+            error(
+                "path",
+                format!(
+                    "The exercise {} is a Cargo exercise, but {} doesn't exist",
+                    exercise.name,
+                    exercise.manifest_path().display()
+                ),
+            );
+        }
+        for construct in exercise
+            .require
+            .iter()
+            .filter(|c| exercise.forbid.contains(c))
+        {
+            error(
+                "forbid",
+                format!(
+                    "The exercise {} both requires and forbids {}",
+                    exercise.name, construct
+                ),
+            );
+        }
+        if let Some(solution) = exercise.solution.as_ref().filter(|s| !s.exists()) {
+            error(
+                "solution",
+                format!(
+                    "The solution of {} points to {}, which doesn't exist",
+                    exercise.name,
+                    solution.display()
+                ),
+            );
+        }
+        for file in exercise.text_files().iter().filter(|f| !f.exists()) {
+            problems.push(Problem {
+                severity: Severity::Error,
+                line: lines.mentioning(i, &file.to_string_lossy()),
+                message: format!(
+                    "The exercise {} reads {}, which doesn't exist",
+                    exercise.name,
+                    file.display()
+                ),
+            });
+        }
+    }
+    problems
+}
+
+// Source files next to the exercises, or modules declared in their mod.rs
+// files, that aren't part of any exercise or solution, with what's wrong
+// with them
+fn unlisted_files(exercises: &[Exercise]) -> BTreeMap<PathBuf, String> {
+    let mod_regex = Regex::new(r"(?m)^\s*(?:pub\s+)?mod\s+(?:r#)?(\w+)\s*;").unwrap();
+    let mut candidates = BTreeMap::new();
+    for file in exercise::rust_files(&exercise::roots(exercises)) {
+        if file.file_name().is_some_and(|name| name == "mod.rs") {
+            let dir = file.parent().unwrap_or(Path::new(""));
+            let source = fs::read_to_string(&file).unwrap_or_default();
+            for module in mod_regex.captures_iter(&source) {
+                // Modules with a directory of their own are topics
+                if dir.join(&module[1]).is_dir() {
+                    continue;
+                }
+                let module = dir.join(format!("{}.rs", &module[1]));
+                let problem = format!(
+                    "{} is declared in {}, but isn't listed",
+                    module.display(),
+                    file.display()
+                );
+                candidates.entry(module).or_insert(problem);
+            }
+        } else {
+            let problem = format!(
+                "{} looks like an exercise, but isn't listed",
+                file.display()
+            );
+            candidates.insert(file, problem);
+        }
+    }
+
+    let listed = |file: &Path| {
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        exercises.iter().any(|e| {
+            e.contains(&file)
+                || e.solution.as_ref().is_some_and(|solution| {
+                    solution
+                        .canonicalize()
+                        .is_ok_and(|solution| file.starts_with(solution))
+                })
+        })
+    };
+    candidates.retain(|file, _| !listed(file));
+    candidates
+}
+
+// Where the exercises are in info.toml
+struct Lines<'a> {
+    lines: Vec<&'a str>,
+    // The index of the `[[exercises]]` line of every exercise
+    tables: Vec<usize>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(toml_str: &'a str) -> Self {
+        let lines: Vec<&str> = toml_str.lines().collect();
+        let tables = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.trim() == "[[exercises]]")
+            .map(|(i, _)| i)
+            .collect();
+        Lines { lines, tables }
+    }
+
+    // The line number of the given key of the exercise at the given index,
+    // or of its `[[exercises]]` line if the key isn't there
+    fn of(&self, exercise: usize, key: &str) -> Option<usize> {
+        self.find(exercise, |line| {
+            line.trim_start()
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        })
+    }
+
+    // The line number of the first line of the exercise at the given index
+    // that contains the given text, e.g. the path of a file it reads, or of
+    // its `[[exercises]]` line if there is none
+    fn mentioning(&self, exercise: usize, text: &str) -> Option<usize> {
+        self.find(exercise, |line| line.contains(text))
+    }
+
+    fn find(&self, exercise: usize, matches: impl Fn(&str) -> bool) -> Option<usize> {
+        let start = *self.tables.get(exercise)?;
+        let end = self
+            .tables
+            .get(exercise + 1)
+            .copied()
+            .unwrap_or(self.lines.len());
+        let line = (start..end)
+            .find(|&i| matches(self.lines[i]))
+            .unwrap_or(start);
+        Some(line + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_every_error_is_reported_with_its_line() {
+        let toml_str = r#"
+[[exercises]]
+name = "twice"
+path = "tests/fixture/state/pending_exercise.rs"
+mode = "compile"
+hint = ""
+
+[[exercises]]
+name = "twice"
+path = "tests/fixture/state/missing.rs"
+mode = "compile"
+hint = ""
+"#;
+        let problems: Vec<String> = parse(toml_str)
+            .unwrap_err()
+            .iter()
+            .map(|p| p.describe(Path::new("info.toml")))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "info.toml:9: error: More than one exercise is called twice (see line 3)",
+                "info.toml:10: error: The exercise twice points to tests/fixture/state/missing.rs, which doesn't exist",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_case_files_are_reported_with_their_line() {
+        let toml_str = r#"
+[[exercises]]
+name = "sum"
+path = "tests/fixture/input/sum.rs"
+mode = "compile"
+hint = ""
+
+[[exercises.cases]]
+input = { file = "tests/fixture/input/numbers.txt" }
+expected_output = { file = "tests/fixture/input/missing.txt" }
+"#;
+        let problems: Vec<String> = parse(toml_str)
+            .unwrap_err()
+            .iter()
+            .map(|p| p.describe(Path::new("info.toml")))
+            .collect();
+        assert_eq!(
+            problems,
+            vec!["info.toml:10: error: The exercise sum reads tests/fixture/input/missing.txt, which doesn't exist"]
+        );
+    }
+
+    #[test]
+    fn test_paths_have_to_stay_inside_the_course() {
+        let toml_str = r#"
+[[exercises]]
+name = "up"
+path = "tests/../tests/fixture/state/pending_exercise.rs"
+mode = "compile"
+hint = ""
+
+[[exercises]]
+name = "absolute"
+path = "/tmp/absolute.rs"
+mode = "compile"
+hint = ""
+"#;
+        let problems: Vec<String> = parse(toml_str)
+            .unwrap_err()
+            .iter()
+            .map(|p| p.describe(Path::new("info.toml")))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "info.toml:4: error: The exercise up points to tests/../tests/fixture/state/pending_exercise.rs, but paths have to be relative to the course, without `..`",
+                "info.toml:10: error: The exercise absolute points to /tmp/absolute.rs, but paths have to be relative to the course, without `..`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_syntax_error_has_its_line() {
+        let problems = parse("[[exercises]]\nname = \"intro1\"\npath = \n").unwrap_err();
+        assert_eq!(problems[0].line, Some(3));
+    }
+
+    #[test]
+    fn test_mode_has_to_match_the_source() {
+        let toml_str = r#"
+[[exercises]]
+name = "no_tests"
+path = "tests/fixture/state/pending_exercise.rs"
+mode = "test"
+hint = "Try it"
+"#;
+        let problems = lint(toml_str);
+        assert_eq!(problems[0].severity, Severity::Warning);
+        assert_eq!(problems[0].line, Some(5));
+        assert_eq!(
+            problems[0].message,
+            "no_tests is in test mode, but has no #[test]"
+        );
+    }
+
+    #[test]
+    fn test_warnings_are_reported_along_with_errors() {
+        let toml_str = r#"
+[[exercises]]
+name = "no_hint"
+path = "tests/fixture/state/pending_exercise.rs"
+mode = "compile"
+hint = ""
+
+[[exercises]]
+name = "missing"
+path = "tests/fixture/state/missing.rs"
+mode = "compile"
+hint = "Try it"
+"#;
+        let problems: Vec<String> = lint(toml_str)
+            .iter()
+            .map(|p| p.describe(Path::new("info.toml")))
+            .collect();
+        assert_eq!(
+            problems[..2],
+            [
+                "info.toml:10: error: The exercise missing points to tests/fixture/state/missing.rs, which doesn't exist",
+                "info.toml:3: warning: no_hint has no hint",
+            ]
+        );
+    }
+}
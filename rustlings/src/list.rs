@@ -0,0 +1,195 @@
+use crate::exercise::{Exercise, Mode};
+use crate::progress::ProgressStore;
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+// How `rustlings list` prints the exercises
+#[derive(PartialEq, Debug)]
+pub enum Format {
+    // Aligned columns for people to read
+    Table,
+    // The exercises and the progress summary in one object, for programs
+    Json,
+    // A header line and a line per exercise, for spreadsheets
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "unknown format {}, pick one of table, json and csv",
+                format
+            )),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Done,
+    Skipped,
+    Pending,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Status::Done => "Done",
+            Status::Skipped => "Skipped",
+            Status::Pending => "Pending",
+        };
+        // Pass the formatter on, so that the table can pad the status
+        status.fmt(f)
+    }
+}
+
+// An exercise, the way it is listed
+#[derive(Serialize)]
+pub struct Row {
+    pub name: String,
+    pub path: String,
+    pub mode: Mode,
+    pub topic: String,
+    pub status: Status,
+    pub attempts: u32,
+    pub hints_used: usize,
+    pub hints: usize,
+}
+
+impl Row {
+    pub fn new(exercise: &Exercise, store: &ProgressStore) -> Row {
+        let status = if store.is_done(exercise) {
+            Status::Done
+        } else if store.is_skipped(exercise) {
+            Status::Skipped
+        } else {
+            Status::Pending
+        };
+        Row {
+            name: exercise.name.clone(),
+            path: exercise.path.display().to_string(),
+            mode: exercise.mode,
+            topic: exercise.topic(),
+            status,
+            attempts: store.attempts(exercise),
+            hints_used: store.hints_used(exercise),
+            hints: exercise.hints().len(),
+        }
+    }
+}
+
+// How far the learner got with the whole course, whatever was listed
+#[derive(Serialize)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+    pub percentage: f32,
+}
+
+impl Progress {
+    pub fn new(exercises: &[Exercise], store: &ProgressStore) -> Progress {
+        let done = exercises.iter().filter(|e| store.is_done(e)).count();
+        Progress {
+            done,
+            total: exercises.len(),
+            percentage: (done as f32 / exercises.len() as f32 * 10000.0).round() / 100.0,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Listing<'a> {
+    exercises: &'a [Row],
+    progress: &'a Progress,
+}
+
+pub fn json(rows: &[Row], progress: &Progress) -> String {
+    let listing = Listing {
+        exercises: rows,
+        progress,
+    };
+    let mut json = serde_json::to_string_pretty(&listing).expect("Failed to serialize to JSON");
+    json.push('\n');
+    json
+}
+
+pub fn csv(rows: &[Row]) -> String {
+    let mut csv = String::from("name,path,mode,topic,status,attempts,hints_used,hints\n");
+    for row in rows {
+        let mode = format!("{:?}", row.mode).to_lowercase();
+        let status = row.status.to_string().to_lowercase();
+        let fields = [
+            csv_field(&row.name),
+            csv_field(&row.path),
+            mode,
+            csv_field(&row.topic),
+            status,
+            row.attempts.to_string(),
+            row.hints_used.to_string(),
+            row.hints.to_string(),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+// Quote the field if it would otherwise be read as more than one
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn table(rows: &[Row], progress: &Progress) -> String {
+    let mut table = format!("{:<17}\t{:<46}\t{:<7}\tHints\n", "Name", "Path", "Status");
+    for row in rows {
+        table.push_str(&format!(
+            "{:<17}\t{:<46}\t{:<7}\t{}/{}\n",
+            row.name, row.path, row.status, row.hints_used, row.hints
+        ));
+    }
+    table.push_str(&summary(progress));
+    table
+}
+
+pub fn summary(progress: &Progress) -> String {
+    format!(
+        "Progress: You completed {} / {} exercises ({:.2} %).\n",
+        progress.done, progress.total, progress.percentage
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_csv_quotes_fields_when_needed() {
+        let row = Row {
+            name: "quiz1".to_string(),
+            path: "exercises/a \"quoted\", odd/quiz1.rs".to_string(),
+            mode: Mode::Test,
+            topic: "a \"quoted\", odd".to_string(),
+            status: Status::Skipped,
+            attempts: 3,
+            hints_used: 1,
+            hints: 2,
+        };
+        assert_eq!(
+            csv(&[row]),
+            "name,path,mode,topic,status,attempts,hints_used,hints\n\
+             quiz1,\"exercises/a \"\"quoted\"\", odd/quiz1.rs\",test,\"a \"\"quoted\"\", odd\",skipped,3,1,2\n"
+        );
+    }
+}
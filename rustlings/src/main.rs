@@ -1,42 +1,74 @@
-use crate::exercise::{Exercise, ExerciseList};
+use crate::check::check_solutions;
+use crate::diagnostics::{explain, print_errors};
+use crate::exercise::{Exercise, ExerciseList, ListChanges, Mode};
+use crate::grade::grade;
+use crate::lint::lint_course;
+use crate::list::{Format, Progress, Row, Status};
+use crate::progress::ProgressStore;
 use crate::project::RustAnalyzerProject;
+use crate::reset::reset;
 use crate::run::run;
-use crate::verify::verify;
+use crate::shell::ShellCommand;
+use crate::solution::solution;
+use crate::verify::{verify, verify_each, Verdict};
 use argh::FromArgs;
 use console::Emoji;
 use notify::DebouncedEvent;
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use std::any::Any;
+use std::collections::BTreeSet;
+use std::env;
 use std::ffi::OsStr;
-use std::fs;
-use std::io::{self, prelude::*};
-use std::path::Path;
+use std::io::{self, prelude::*, IsTerminal};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{channel, RecvTimeoutError};
-use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
 use std::thread;
 use std::time::Duration;
 
 #[macro_use]
 mod ui;
 
+mod cache;
+mod check;
+mod course;
+mod diagnostics;
+mod diff;
 mod exercise;
+mod grade;
+mod libtest;
+mod lint;
+mod list;
+mod progress;
 mod project;
+mod report;
+mod reset;
+mod rules;
 mod run;
+mod shell;
+mod solution;
+mod tamper;
+mod tui;
 mod verify;
 
 // In sync with crate version
 const VERSION: &str = "4.7.1";
+// Looking for changed files more often than this would keep the CPU busy
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(200);
 
 #[derive(FromArgs, PartialEq, Debug)]
 /// Rustlings is a collection of small exercises to get you used to writing and reading Rust code
 struct Args {
-    /// show outputs from the test exercises
+    /// show outputs from the test exercises and all compiler errors
     #[argh(switch)]
     nocapture: bool,
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// the course to work on: a directory with an info.toml, or the name of an
+    /// installed course. Defaults to $RUSTLINGS_COURSE, or the current directory.
+    #[argh(option)]
+    course: Option<String>,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -48,6 +80,13 @@ enum Subcommands {
     Watch(WatchArgs),
     Run(RunArgs),
     Hint(HintArgs),
+    Solution(SolutionArgs),
+    CheckSolutions(CheckSolutionsArgs),
+    Courses(CoursesArgs),
+    Grade(GradeArgs),
+    LintCourse(LintCourseArgs),
+    Reset(ResetArgs),
+    Explain(ExplainArgs),
     List(ListArgs),
     Lsp(LspArgs),
 }
@@ -55,12 +94,36 @@ enum Subcommands {
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "verify")]
 /// Verifies all exercises according to the recommended order
-struct VerifyArgs {}
+struct VerifyArgs {
+    #[argh(option, short = 'j', default = "1")]
+    /// how many exercises to compile and test in parallel
+    jobs: usize,
+    #[argh(switch)]
+    /// verify every exercise instead of stopping at the first one that fails
+    keep_going: bool,
+    #[argh(option)]
+    /// write a report of how every exercise did to this file
+    report: Option<PathBuf>,
+    #[argh(option, default = "report::Format::Junit")]
+    /// the format of the report: junit (default) or json
+    report_format: report::Format,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "watch")]
 /// Reruns `verify` when files were edited
-struct WatchArgs {}
+struct WatchArgs {
+    #[argh(switch)]
+    /// read commands line by line instead of showing a full-screen interface
+    no_tui: bool,
+    #[argh(switch)]
+    /// look for changed files every so often instead of relying on file system events,
+    /// which never arrive in some containers and network file systems
+    poll: bool,
+    #[argh(option, default = "2000")]
+    /// how many milliseconds to wait for more changes before checking an exercise (default 2000)
+    debounce_ms: u64,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "run")]
@@ -69,6 +132,9 @@ struct RunArgs {
     #[argh(positional)]
     /// the name of the exercise
     name: String,
+    #[argh(option)]
+    /// run only the tests whose names contain this
+    test: Option<String>,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -80,6 +146,73 @@ struct HintArgs {
     name: String,
 }
 
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "solution")]
+/// Shows the reference solution of an exercise next to your version
+struct SolutionArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: String,
+    #[argh(switch)]
+    /// show the solution before the exercise is done, which is remembered
+    give_up: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "check-solutions")]
+/// Checks that the solutions pass and the exercises don't (for course maintainers)
+struct CheckSolutionsArgs {
+    #[argh(positional)]
+    /// the name of the exercise, or a directory of exercises, to check
+    name: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "courses")]
+/// Lists the installed courses, which can be picked with `--course`
+struct CoursesArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "grade")]
+/// Verifies the exercises of students' checkouts and writes a gradebook (for instructors)
+struct GradeArgs {
+    #[argh(positional)]
+    /// the rustlings directories of the students
+    students: Vec<PathBuf>,
+    #[argh(option, short = 'o', default = "PathBuf::from(\"gradebook.csv\")")]
+    /// where to write the gradebook (default gradebook.csv)
+    output: PathBuf,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "lint-course")]
+/// Reports every problem of info.toml and the exercises (for course maintainers)
+struct LintCourseArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "reset")]
+/// Restores exercises to their original contents
+struct ResetArgs {
+    #[argh(positional)]
+    /// the name of the exercise, or a directory of exercises
+    name: Option<String>,
+    #[argh(switch)]
+    /// reset all exercises
+    all: bool,
+    #[argh(switch)]
+    /// overwrite the exercises, even though your changes will be lost
+    force: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "explain")]
+/// Explains a compiler error code in detail
+struct ExplainArgs {
+    #[argh(positional)]
+    /// the error code, e.g. E0308
+    code: String,
+}
+
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "lsp")]
 /// Enable rust-analyzer for exercises
@@ -105,10 +238,14 @@ struct ListArgs {
     #[argh(switch, short = 's')]
     /// display only exercises that have been solved
     solved: bool,
+    #[argh(option, default = "Format::Table")]
+    /// how to print the exercises: table (default), json or csv. JSON also
+    /// has the progress summary, CSV only the exercises
+    format: Format,
 }
 
 fn main() {
-    let args: Args = argh::from_env();
+    let mut args: Args = argh::from_env();
 
     if args.version {
         println!("v{}", VERSION);
@@ -119,12 +256,34 @@ fn main() {
         println!("\n{}\n", WELCOME);
     }
 
+    // Everything else, e.g. the progress, is kept in the directory of the
+    // course, so working there is all it takes to switch between courses
+    if let Some(course) = args.course.or_else(|| env::var("RUSTLINGS_COURSE").ok()) {
+        let dir = course::find(&course).unwrap_or_else(|e| {
+            println!("{}", e);
+            println!("Run `rustlings courses` to see the installed courses.");
+            std::process::exit(1);
+        });
+        if let (Some(command), Ok(cwd)) = (args.nested.as_mut(), env::current_dir()) {
+            resolve_paths(command, &cwd);
+        }
+        if let Err(e) = env::set_current_dir(&dir) {
+            println!("Could not switch to the course at {}: {}", dir.display(), e);
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(Subcommands::Courses(_)) = args.nested {
+        list_courses();
+        std::process::exit(0);
+    }
+
     if !Path::new("info.toml").exists() {
         println!(
             "{} must be run from the rustlings directory",
             std::env::current_exe().unwrap().to_str().unwrap()
         );
-        println!("Try `cd rustlings/`!");
+        println!("Try `cd rustlings/`, or pick a course with `--course`!");
         std::process::exit(1);
     }
 
@@ -135,8 +294,30 @@ fn main() {
         std::process::exit(1);
     }
 
-    let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
+    // Comes before loading the exercises, which gives up on any error
+    if let Some(Subcommands::LintCourse(_)) = args.nested {
+        match lint_course(Path::new("info.toml")) {
+            Ok(()) => std::process::exit(0),
+            Err(()) => std::process::exit(1),
+        }
+    }
+
+    let exercises = ExerciseList::load(Path::new("info.toml")).unwrap_or_else(|e| {
+        println!("{}", e);
+        std::process::exit(1);
+    });
+    let mut store = ProgressStore::load();
+    if let Err(e) = reset::store_originals(&exercises) {
+        println!("Failed to keep a copy of the original exercises: {}", e);
+    }
+    match tamper::record(&exercises, &store) {
+        Ok(warnings) => {
+            for warning in warnings {
+                warn!("{}", warning);
+            }
+        }
+        Err(e) => println!("Failed to record the tests of the exercises: {}", e),
+    }
     let verbose = args.nocapture;
 
     let command = args.nested.unwrap_or_else(|| {
@@ -145,104 +326,200 @@ fn main() {
     });
     match command {
         Subcommands::List(subargs) => {
-            if !subargs.paths && !subargs.names {
-                println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
-            }
-            let mut exercises_done: u16 = 0;
             let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
-            exercises.iter().for_each(|e| {
-                let fname = format!("{}", e.path.display());
-                let filter_cond = filters
-                    .split(',')
-                    .filter(|f| !f.trim().is_empty())
-                    .any(|f| e.name.contains(&f) || fname.contains(&f));
-                let status = if e.looks_done() {
-                    exercises_done += 1;
-                    "Done"
-                } else {
-                    "Pending"
-                };
-                let solve_cond = {
-                    (e.looks_done() && subargs.solved)
-                        || (!e.looks_done() && subargs.unsolved)
-                        || (!subargs.solved && !subargs.unsolved)
-                };
-                if solve_cond && (filter_cond || subargs.filter.is_none()) {
-                    let line = if subargs.paths {
-                        format!("{}\n", fname)
-                    } else if subargs.names {
-                        format!("{}\n", e.name)
-                    } else {
-                        format!("{:<17}\t{:<46}\t{:<7}\n", e.name, fname, status)
+            let rows: Vec<Row> = exercises
+                .iter()
+                .map(|e| Row::new(e, &store))
+                .filter(|row| {
+                    let filter_cond = filters
+                        .split(',')
+                        .filter(|f| !f.trim().is_empty())
+                        .any(|f| row.name.contains(f) || row.path.contains(f));
+                    let done = row.status == Status::Done;
+                    let solve_cond = {
+                        (done && subargs.solved)
+                            || (!done && subargs.unsolved)
+                            || (!subargs.solved && !subargs.unsolved)
                     };
-                    // Somehow using println! leads to the binary panicking
-                    // when its output is piped.
-                    // So, we're handling a Broken Pipe error and exiting with 0 anyway
-                    let stdout = std::io::stdout();
-                    {
-                        let mut handle = stdout.lock();
-                        handle.write_all(line.as_bytes()).unwrap_or_else(|e| {
-                            match e.kind() {
-                                std::io::ErrorKind::BrokenPipe => std::process::exit(0),
-                                _ => std::process::exit(1),
-                            };
-                        });
-                    }
+                    solve_cond && (filter_cond || subargs.filter.is_none())
+                })
+                .collect();
+            let progress = Progress::new(&exercises, &store);
+            let output = match subargs.format {
+                Format::Json => list::json(&rows, &progress),
+                Format::Csv => list::csv(&rows),
+                Format::Table if subargs.paths || subargs.names => {
+                    let mut output: String = rows
+                        .iter()
+                        .map(|row| {
+                            let column = if subargs.paths { &row.path } else { &row.name };
+                            format!("{}\n", column)
+                        })
+                        .collect();
+                    output.push_str(&list::summary(&progress));
+                    output
                 }
-            });
-            let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
-            println!(
-                "Progress: You completed {} / {} exercises ({:.2} %).",
-                exercises_done,
-                exercises.len(),
-                percentage_progress
-            );
+                Format::Table => list::table(&rows, &progress),
+            };
+            // Somehow using println! leads to the binary panicking
+            // when its output is piped.
+            // So, we're handling a Broken Pipe error and exiting with 0 anyway
+            let stdout = std::io::stdout();
+            {
+                let mut handle = stdout.lock();
+                handle.write_all(output.as_bytes()).unwrap_or_else(|e| {
+                    match e.kind() {
+                        std::io::ErrorKind::BrokenPipe => std::process::exit(0),
+                        _ => std::process::exit(1),
+                    };
+                });
+            }
             std::process::exit(0);
         }
 
         Subcommands::Run(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let exercise = find_exercise(&subargs.name, &exercises, &store);
 
-            run(exercise, verbose).unwrap_or_else(|_| std::process::exit(1));
+            run(exercise, &mut store, verbose, subargs.test.as_deref())
+                .unwrap_or_else(|_| std::process::exit(1));
         }
 
         Subcommands::Hint(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let exercise = find_exercise(&subargs.name, &exercises, &store);
 
-            println!("{}", exercise.hint);
+            print_hint(exercise, &mut store);
         }
 
-        Subcommands::Verify(_subargs) => {
-            verify(&exercises, (0, exercises.len()), verbose)
+        Subcommands::Solution(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises, &store);
+
+            solution(exercise, &mut store, subargs.give_up)
                 .unwrap_or_else(|_| std::process::exit(1));
         }
 
+        Subcommands::CheckSolutions(subargs) => {
+            let selected = match &subargs.name {
+                Some(name) => reset::select(name, &exercises),
+                None => exercises.iter().collect(),
+            };
+            if selected.is_empty() {
+                println!(
+                    "No exercise found for '{}'!",
+                    subargs.name.unwrap_or_default()
+                );
+                std::process::exit(1);
+            }
+            check_solutions(&selected).unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::Grade(subargs) => {
+            if subargs.students.is_empty() {
+                println!("Please name the directories of the students to grade.");
+                std::process::exit(1);
+            }
+            grade(&exercises, &subargs.students, &subargs.output)
+                .unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::Courses(_) | Subcommands::LintCourse(_) => {
+            unreachable!("handled before loading the exercises")
+        }
+
+        Subcommands::Reset(subargs) => {
+            let selected = match (&subargs.name, subargs.all) {
+                (_, true) => exercises.iter().collect(),
+                (Some(name), false) => reset::select(name, &exercises),
+                (None, false) => {
+                    println!("Please name the exercise or directory to reset, or pass `--all`.");
+                    std::process::exit(1);
+                }
+            };
+            if selected.is_empty() {
+                println!(
+                    "No exercise found for '{}'!",
+                    subargs.name.unwrap_or_default()
+                );
+                std::process::exit(1);
+            }
+
+            reset(&selected, subargs.force).unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::Explain(subargs) => {
+            explain(&subargs.code).unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::Verify(subargs) => {
+            let records = verify_each(
+                &exercises,
+                (0, exercises.len()),
+                &mut store,
+                verbose,
+                subargs.jobs,
+                subargs.keep_going,
+            );
+            if let Some(path) = &subargs.report {
+                if let Err(e) = report::write(&records, &subargs.report_format, path) {
+                    println!("Failed to write the report to {}: {}", path.display(), e);
+                    std::process::exit(1);
+                }
+            }
+            let done = records
+                .iter()
+                .filter(|r| r.verdict == Verdict::Done)
+                .count();
+            if subargs.keep_going {
+                println!("{} of {} exercises are done.", done, records.len());
+            }
+            if done < records.len() {
+                std::process::exit(1);
+            }
+        }
+
         Subcommands::Lsp(_subargs) => {
             let mut project = RustAnalyzerProject::new();
             project
                 .get_sysroot_src()
                 .expect("Couldn't find toolchain path, do you have `rustc` installed?");
+            let cargo_exercises: Vec<&Exercise> = exercises
+                .iter()
+                .filter(|e| matches!(e.mode, Mode::Cargo))
+                .collect();
             project
-                .exercies_to_json()
+                .exercies_to_json(&exercises)
                 .expect("Couldn't parse rustlings exercises files");
 
-            if project.crates.is_empty() {
+            if project.crates.is_empty() && cargo_exercises.is_empty() {
                 println!("Failed find any exercises, make sure you're in the `rustlings` folder");
             } else if project.write_to_disk().is_err() {
                 println!("Failed to write rust-project.json to disk for rust-analyzer");
             } else {
                 println!("Successfully generated rust-project.json");
-                println!("rust-analyzer will now parse exercises, restart your language server or editor")
+                println!("rust-analyzer will now parse exercises, restart your language server or editor");
+                if !cargo_exercises.is_empty() {
+                    println!();
+                    println!("Cargo exercises are packages of their own. For rust-analyzer to find them,");
+                    println!("set its `linkedProjects` option in your editor to:");
+                    println!("{}", project.linked_projects(&cargo_exercises));
+                }
             }
         }
 
-        Subcommands::Watch(_subargs) => match watch(&exercises, verbose) {
+        Subcommands::Watch(subargs) => match watch(
+            exercises,
+            &mut store,
+            verbose,
+            !subargs.no_tui && io::stdin().is_terminal() && io::stdout().is_terminal(),
+            subargs.poll,
+            Duration::from_millis(subargs.debounce_ms),
+        ) {
             Err(e) => {
                 println!(
                     "Error: Could not watch your progress. Error message was {:?}.",
                     e
                 );
                 println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+                println!("If changes to your files go unnoticed, try `rustlings watch --poll`.");
                 std::process::exit(1);
             }
             Ok(WatchStatus::Finished) => {
@@ -260,49 +537,145 @@ fn main() {
     }
 }
 
-fn spawn_watch_shell(
-    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
-    should_quit: Arc<AtomicBool>,
-) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
+// Read commands from stdin and pass them on to the watch loop
+fn spawn_watch_shell(commands: Sender<ShellCommand>) {
     println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
     thread::spawn(move || loop {
         let mut input = String::new();
         match io::stdin().read_line(&mut input) {
-            Ok(_) => {
-                let input = input.trim();
-                if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{}", hint);
+            Ok(0) => break,
+            Ok(_) if input.trim().is_empty() => {}
+            Ok(_) => match ShellCommand::parse(&input) {
+                Ok(command) => {
+                    if commands.send(command).is_err() {
+                        break;
                     }
-                } else if input == "clear" {
-                    println!("\x1B[2J\x1B[1;1H");
-                } else if input.eq("quit") {
-                    should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
-                } else if input.eq("help") {
-                    println!("Commands available to you in watch mode:");
-                    println!("  hint  - prints the current exercise's hint");
-                    println!("  clear - clears the screen");
-                    println!("  quit  - quits watch mode");
-                    println!("  help  - displays this help message");
-                    println!();
-                    println!("Watch mode automatically re-evaluates the current exercise");
-                    println!("when you edit a file's contents.")
-                } else {
-                    println!("unknown command: {}", input);
                 }
-            }
+                Err(e) => println!("{}", e),
+            },
             Err(error) => println!("error reading command: {}", error),
         }
     });
 }
 
-fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
+// Compile the exercise again to show all of its errors in full (`errors`),
+// or the explanation of the first one (`explain`) or of a given code (`explain E0308`)
+fn inspect_errors(exercise: &Exercise, command: ShellCommand) {
+    let output = match exercise.compile() {
+        Ok(_) => {
+            println!("{} compiles without errors.", exercise);
+            return;
+        }
+        Err(output) => output,
+    };
+    let code = match command {
+        ShellCommand::Explain(code) => code,
+        _ => {
+            print_errors(&output.diagnostics, &output.stderr, true);
+            return;
+        }
+    };
+
+    let first_code = output
+        .diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .find_map(|d| d.code.as_ref())
+        .map(|code| code.code.clone());
+    match code.or(first_code) {
+        Some(code) => {
+            let _ = explain(&code);
+        }
+        None => println!("The errors of {} have no detailed explanation.", exercise),
+    }
+}
+
+// Reveal the next hint of the exercise, along with the ones revealed before
+fn print_hint(exercise: &Exercise, store: &mut ProgressStore) {
+    let hints = exercise.hints();
+    if hints.is_empty() {
+        println!("There are no hints for {}.", exercise);
+        return;
+    }
+    let revealed = store.reveal_hint(exercise).min(hints.len());
+    if hints.len() == 1 {
+        println!("{}", hints[0]);
+        return;
+    }
+    for (i, hint) in hints[..revealed].iter().enumerate() {
+        println!("Hint {} of {}:", i + 1, hints.len());
+        println!("{}\n", hint.trim_end());
+    }
+    if revealed < hints.len() {
+        println!("Ask for a hint again to reveal the next one.");
+    }
+}
+
+// List the installed courses, marking the one that is worked on
+fn list_courses() {
+    let dir = match course::courses_dir() {
+        Some(dir) => dir,
+        None => {
+            println!("Could not find the home directory, set RUSTLINGS_COURSES_DIR instead.");
+            return;
+        }
+    };
+    let courses = course::installed();
+    if courses.is_empty() {
+        println!("There are no courses in {}.", dir.display());
+        println!("Copy a directory with an info.toml there to install a course.");
+        return;
+    }
+    println!("Courses in {}:", dir.display());
+    let current = env::current_dir().and_then(|dir| dir.canonicalize()).ok();
+    for course in courses {
+        let marker = if course.dir.canonicalize().ok() == current {
+            "▶"
+        } else {
+            " "
+        };
+        let exercises = match course.exercise_count() {
+            Some(count) => format!("{} exercises", count),
+            None => "invalid info.toml".to_string(),
+        };
+        println!("{} {:<17}\t{}", marker, course.name, exercises);
+    }
+}
+
+// List the exercises that are not done yet, in the order they come up
+fn print_pending(exercises: &[Exercise], store: &ProgressStore, current: &Exercise) {
+    let pending = store.pending(exercises);
+    if pending.is_empty() {
+        println!("All exercises are done!");
+        return;
+    }
+    println!("Exercises that are not done yet:");
+    for exercise in pending {
+        let marker = if exercise.name == current.name {
+            "▶"
+        } else {
+            " "
+        };
+        let status = if store.is_skipped(exercise) {
+            " (skipped)"
+        } else {
+            ""
+        };
+        println!(
+            "{} {:<17}\t{}{}",
+            marker,
+            exercise.name,
+            exercise.path.display(),
+            status
+        );
+    }
+}
+
+fn find_exercise<'a>(name: &str, exercises: &'a [Exercise], store: &ProgressStore) -> &'a Exercise {
     if name.eq("next") {
         exercises
             .iter()
-            .find(|e| !e.looks_done())
+            .find(|e| !store.is_done(e))
             .unwrap_or_else(|| {
                 println!("🎉 Congratulations! You have done all the exercises!");
                 println!("🔚 There are no more exercises to do next!");
@@ -324,67 +697,281 @@ enum WatchStatus {
     Unfinished,
 }
 
-fn watch(exercises: &[Exercise], verbose: bool) -> notify::Result<WatchStatus> {
-    /* Clears the terminal with an ANSI escape code.
-    Works in UNIX and newer Windows terminals. */
-    fn clear_screen() {
-        println!("\x1Bc");
+// What an event of the watcher is about
+enum Change {
+    // A source file of an exercise was saved
+    File(PathBuf),
+    // info.toml was saved
+    ExerciseList,
+}
+
+fn change(event: DebouncedEvent) -> Option<Change> {
+    match event {
+        DebouncedEvent::Create(b)
+        | DebouncedEvent::Chmod(b)
+        | DebouncedEvent::Write(b)
+        | DebouncedEvent::Rename(_, b) => {
+            if b.file_name() == Some(OsStr::new("info.toml")) {
+                let info_toml = Path::new("info.toml").canonicalize().ok();
+                return (b.canonicalize().ok() == info_toml).then_some(Change::ExerciseList);
+            }
+            let is_source = b.extension() == Some(OsStr::new("rs"))
+                || b.file_name() == Some(OsStr::new("Cargo.toml"));
+            if is_source && b.exists() {
+                b.canonicalize().ok().map(Change::File)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+// Load info.toml again after it was changed, keeping a copy of the new
+// exercises for `rustlings reset` and recording their tests. If it became
+// invalid, nothing changes. Nothing is printed here, the full-screen
+// interface shows the warnings among the changes instead.
+fn reload(exercises: &mut Vec<Exercise>, store: &ProgressStore) -> Result<ListChanges, String> {
+    let reloaded = ExerciseList::load(Path::new("info.toml"))?;
+    let mut changes = ListChanges::between(exercises, &reloaded);
+    if let Err(e) = reset::store_originals(&reloaded) {
+        changes.warnings.push(format!(
+            "Failed to keep a copy of the original exercises: {}",
+            e
+        ));
+    }
+    match tamper::record(&reloaded, store) {
+        Ok(warnings) => changes.warnings.extend(warnings),
+        Err(e) => changes.warnings.push(format!(
+            "Failed to record the tests of the exercises: {}",
+            e
+        )),
     }
+    *exercises = reloaded;
+    Ok(changes)
+}
 
-    let (tx, rx) = channel();
-    let should_quit = Arc::new(AtomicBool::new(false));
+// Paths given on the command line are relative to where rustlings was
+// started, not to the course it switches to
+fn resolve_paths(command: &mut Subcommands, dir: &Path) {
+    match command {
+        Subcommands::Verify(args) => {
+            if let Some(report) = args.report.as_mut() {
+                *report = dir.join(&report);
+            }
+        }
+        Subcommands::Grade(args) => {
+            for student in args.students.iter_mut() {
+                *student = dir.join(&student);
+            }
+            args.output = dir.join(&args.output);
+        }
+        _ => {}
+    }
+}
 
-    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(2))?;
-    watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
+// Watch the exercises and info.toml for changes. File system events are
+// used where possible, polling the files is the fallback.
+fn watch_files(
+    tx: Sender<DebouncedEvent>,
+    roots: &BTreeSet<PathBuf>,
+    poll: bool,
+    debounce: Duration,
+) -> notify::Result<Box<dyn Any>> {
+    if !poll {
+        match watch_paths::<RecommendedWatcher>(tx.clone(), roots, debounce) {
+            Ok(watcher) => return Ok(Box::new(watcher)),
+            Err(e) => warn!(
+                "Could not watch for file system events, looking for changes every so often instead: {:?}",
+                e
+            ),
+        }
+    }
+    let interval = poll_interval(debounce);
+    Ok(Box::new(watch_paths::<PollWatcher>(tx, roots, interval)?))
+}
 
-    clear_screen();
+// The poll watcher looks at the files as often as it debounces,
+// but never more often than `MIN_POLL_INTERVAL`
+fn poll_interval(debounce: Duration) -> Duration {
+    debounce.max(MIN_POLL_INTERVAL)
+}
 
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-    let failed_exercise_hint = match verify(exercises.iter(), (0, exercises.len()), verbose) {
-        Ok(_) => return Ok(WatchStatus::Finished),
-        Err(exercise) => Arc::new(Mutex::new(Some(to_owned_hint(exercise)))),
+fn watch_paths<W: Watcher>(
+    tx: Sender<DebouncedEvent>,
+    roots: &BTreeSet<PathBuf>,
+    debounce: Duration,
+) -> notify::Result<W> {
+    let mut watcher = W::new(tx, debounce)?;
+    for root in roots {
+        watcher.watch(root, RecursiveMode::Recursive)?;
+    }
+    // info.toml itself may be replaced when saved, so watch its directory
+    watcher.watch(Path::new("."), RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+fn watch(
+    mut exercises: Vec<Exercise>,
+    store: &mut ProgressStore,
+    verbose: bool,
+    full_screen: bool,
+    poll: bool,
+    debounce: Duration,
+) -> notify::Result<WatchStatus> {
+    let (tx, rx) = channel();
+    // Exercises that a reload of info.toml adds elsewhere are only
+    // watched from the next start on
+    let _watcher = watch_files(tx, &exercise::roots(&exercises), poll, debounce)?;
+
+    if full_screen {
+        return tui::watch(exercises, store, verbose, &rx).map_err(notify::Error::Io);
+    }
+
+    let Some(first) = store.pending(&exercises).first().copied() else {
+        return Ok(WatchStatus::Finished);
     };
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
+    // The name of the exercise the learner is working on
+    let mut focus = match verify_focus(first, &exercises, store, verbose) {
+        Some(exercise) => exercise.name.clone(),
+        None => return Ok(WatchStatus::Finished),
+    };
+    let (command_tx, command_rx) = channel();
+    spawn_watch_shell(command_tx);
     loop {
-        match rx.recv_timeout(Duration::from_secs(1)) {
-            Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises
-                            .iter()
-                            .find(|e| filepath.ends_with(&e.path))
-                            .into_iter()
-                            .chain(
-                                exercises
-                                    .iter()
-                                    .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)),
-                            );
-                        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
-                        clear_screen();
-                        match verify(pending_exercises, (num_done, exercises.len()), verbose) {
-                            Ok(_) => return Ok(WatchStatus::Finished),
-                            Err(exercise) => {
-                                let mut failed_exercise_hint = failed_exercise_hint.lock().unwrap();
-                                *failed_exercise_hint = Some(to_owned_hint(exercise));
-                            }
+        // The exercise to verify and focus on, if anything asks for it
+        let mut to_focus = None;
+        let mut changes = None;
+        match rx.recv_timeout(Duration::from_millis(200)) {
+            Ok(event) => match change(event) {
+                Some(Change::File(filepath)) => {
+                    to_focus = exercises
+                        .iter()
+                        .find(|e| e.contains(&filepath))
+                        .map(|e| e.name.clone());
+                }
+                Some(Change::ExerciseList) => match reload(&mut exercises, store) {
+                    Ok(reloaded) => {
+                        if !exercises.iter().any(|e| e.name == focus) {
+                            let pending = store.pending(&exercises);
+                            focus = pending.first().unwrap_or(&&exercises[0]).name.clone();
                         }
+                        to_focus = Some(focus.clone());
+                        changes = Some(reloaded);
                     }
-                }
-                _ => {}
+                    Err(e) => warn!("Kept the exercises as they were: {}", e),
+                },
+                None => {}
             },
             Err(RecvTimeoutError::Timeout) => {
-                // the timeout expired, just check the `should_quit` variable below then loop again
+                // the timeout expired, just look for commands below then loop again
             }
             Err(e) => println!("watch error: {:?}", e),
         }
-        // Check if we need to exit
-        if should_quit.load(Ordering::SeqCst) {
-            return Ok(WatchStatus::Unfinished);
+
+        let current = exercises
+            .iter()
+            .find(|e| e.name == focus)
+            .unwrap_or(&exercises[0]);
+        for command in command_rx.try_iter() {
+            match command {
+                ShellCommand::Hint => print_hint(current, store),
+                ShellCommand::Errors | ShellCommand::Explain(_) => inspect_errors(current, command),
+                ShellCommand::Run => to_focus = Some(focus.clone()),
+                ShellCommand::Next | ShellCommand::Skip => {
+                    if command == ShellCommand::Skip {
+                        store.skip(current);
+                        println!(
+                            "Skipped {}, it will come up again once the others are done.",
+                            current
+                        );
+                    }
+                    to_focus = store
+                        .pending_after(&exercises, current)
+                        .into_iter()
+                        .find(|e| e.name != focus)
+                        .map(|e| e.name.clone());
+                    if to_focus.is_none() {
+                        println!("There are no other exercises left to do.");
+                    }
+                }
+                ShellCommand::Goto(name) => {
+                    if exercises.iter().any(|e| e.name == name) {
+                        to_focus = Some(name);
+                    } else {
+                        println!("No exercise found for '{}'!", name);
+                    }
+                }
+                ShellCommand::List => print_pending(&exercises, store, current),
+                ShellCommand::Path => println!("{}", current.path.display()),
+                ShellCommand::Clear => println!("\x1B[2J\x1B[1;1H"),
+                ShellCommand::Quit => {
+                    println!("Bye!");
+                    return Ok(WatchStatus::Unfinished);
+                }
+                ShellCommand::Help => {
+                    println!("{}", shell::HELP);
+                    println!();
+                    println!("Watch mode automatically re-evaluates the exercise");
+                    println!("whose file you edited, and focuses on it.")
+                }
+            }
+        }
+
+        if let Some(name) = to_focus {
+            let exercise = exercises.iter().find(|e| e.name == name);
+            match exercise.and_then(|e| verify_focus(e, &exercises, store, verbose)) {
+                Some(exercise) => focus = exercise.name.clone(),
+                None => return Ok(WatchStatus::Finished),
+            }
+        }
+        if let Some(changes) = changes {
+            println!("{}", changes);
+        }
+    }
+}
+
+// Verify the exercise the learner is working on and show only its result.
+// Once it is done, the focus moves on to the exercises after it that are not
+// done yet, unless the learner is just reviewing an exercise finished before.
+// Returns the exercise to focus on next, or None once all exercises are done.
+fn verify_focus<'a>(
+    focus: &'a Exercise,
+    exercises: &'a [Exercise],
+    store: &mut ProgressStore,
+    verbose: bool,
+) -> Option<&'a Exercise> {
+    let reviewing = store.is_done(focus);
+    let mut to_verify = vec![focus];
+    if !reviewing {
+        to_verify.extend(
+            store
+                .pending_after(exercises, focus)
+                .into_iter()
+                .filter(|e| e.name != focus.name),
+        );
+    }
+    let num_done = exercises.len() - store.pending(exercises).len();
+    clear_screen();
+    match verify(to_verify, (num_done, exercises.len()), store, verbose, 1) {
+        Ok(_) if reviewing => {
+            success!(
+                "{} is done! Keep reviewing it, or type `next` to move on.",
+                focus
+            );
+            Some(focus)
         }
+        Ok(_) => None,
+        Err(exercise) => Some(exercise),
     }
 }
 
+/* Clears the terminal with an ANSI escape code.
+Works in UNIX and newer Windows terminals. */
+fn clear_screen() {
+    println!("\x1Bc");
+}
+
 fn rustc_exists() -> bool {
     Command::new("rustc")
         .args(&["--version"])
@@ -412,8 +999,8 @@ started, here's a couple of notes about how Rustlings operates:
    up as soon as you run Rustlings! This is part of the exercise that you're
    supposed to solve, so open the exercise file in an editor and start your
    detective work!
-3. If you're stuck on an exercise, there is a helpful hint you can view by typing
-   'hint' (in watch mode), or running `rustlings hint exercise_name`.
+3. If you're stuck on an exercise, there is a helpful hint you can view by pressing
+   'h' (in watch mode), or running `rustlings hint exercise_name`.
 4. If an exercise doesn't make sense to you, feel free to open an issue on GitHub!
    (https://github.com/rust-lang/rustlings/issues/new). We look at every issue,
    and sometimes, other learners do too so you can help each other out!
@@ -457,3 +1044,48 @@ const WELCOME: &str = r#"       welcome to...
  | |  | |_| \__ \ |_| | | | | | (_| \__ \
  |_|   \__,_|___/\__|_|_|_| |_|\__, |___/
                                |___/"#;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_watch_args() {
+        let args = Args::from_args(&["rustlings"], &["watch", "--poll", "--debounce-ms", "50"]);
+        assert_eq!(
+            args.unwrap().nested,
+            Some(Subcommands::Watch(WatchArgs {
+                no_tui: false,
+                poll: true,
+                debounce_ms: 50,
+            }))
+        );
+
+        let args = Args::from_args(&["rustlings"], &["watch"]);
+        assert_eq!(
+            args.unwrap().nested,
+            Some(Subcommands::Watch(WatchArgs {
+                no_tui: false,
+                poll: false,
+                debounce_ms: 2000,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_poll_selects_poll_watcher() {
+        let (tx, _rx) = channel();
+        let roots = BTreeSet::from([PathBuf::from("tests/fixture/input")]);
+        let watcher = watch_files(tx, &roots, true, Duration::from_millis(50)).unwrap();
+        assert!(watcher.downcast::<PollWatcher>().is_ok());
+    }
+
+    #[test]
+    fn test_poll_interval_is_clamped() {
+        assert_eq!(poll_interval(Duration::from_millis(50)), MIN_POLL_INTERVAL);
+        assert_eq!(
+            poll_interval(Duration::from_secs(2)),
+            Duration::from_secs(2)
+        );
+    }
+}
@@ -0,0 +1,357 @@
+use crate::exercise::Exercise;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const PROGRESS_FILE_PATH: &str = ".rustlings-state.json";
+
+/// The progress of a learner through the exercises,
+/// persisted in `.rustlings-state.json` next to `info.toml`
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct ProgressStore {
+    #[serde(default)]
+    exercises: BTreeMap<String, ExerciseProgress>,
+}
+
+/// What rustlings knows about a single exercise
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+pub struct ExerciseProgress {
+    /// How many times the exercise was compiled, run or tested
+    #[serde(default)]
+    pub attempts: u32,
+    /// Unix timestamp of the last time the exercise compiled and passed
+    #[serde(default)]
+    pub last_success: Option<u64>,
+    /// Hash of the exercise's source at the time of `last_success`
+    #[serde(default)]
+    pub source_hash: Option<String>,
+    /// Whether the learner chose to skip the exercise for now
+    #[serde(default)]
+    pub skipped: bool,
+    /// How many of the exercise's hints the learner has revealed
+    #[serde(default)]
+    pub hints_used: usize,
+    /// Whether the learner looked at the solution before finishing the exercise
+    #[serde(default)]
+    pub gave_up: bool,
+}
+
+impl ExerciseProgress {
+    /// Count an attempt at the exercise. A failing attempt forgets the
+    /// last success, as the exercise isn't done with its current source,
+    /// or its current checks in info.toml, anymore.
+    fn attempt(&mut self, exercise: &Exercise, passed: bool) {
+        self.attempts += 1;
+        if passed {
+            self.last_success = Some(now());
+            self.source_hash = Some(exercise.source_hash());
+        } else {
+            self.last_success = None;
+            self.source_hash = None;
+        }
+    }
+}
+
+impl ProgressStore {
+    /// Load the progress store, starting from scratch if there is none yet
+    pub fn load() -> ProgressStore {
+        match Self::read() {
+            Ok(store) => store,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => ProgressStore::default(),
+            Err(e) => {
+                warn!("Could not read your progress, starting from scratch: {}", e);
+                ProgressStore::default()
+            }
+        }
+    }
+
+    fn read() -> io::Result<ProgressStore> {
+        let contents = fs::read_to_string(PROGRESS_FILE_PATH)?;
+        serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Write the progress store to disk.
+    /// The file is replaced atomically so that a concurrently running
+    /// rustlings process never sees a half-written file.
+    pub fn save(&self) -> io::Result<()> {
+        let tmp_path = format!("{}.{}.tmp", PROGRESS_FILE_PATH, process::id());
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, Path::new(PROGRESS_FILE_PATH))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ExerciseProgress> {
+        self.exercises.get(name)
+    }
+
+    /// Record an attempt at the given exercise and persist it right away.
+    /// Passing attempts also remember the source they passed with.
+    pub fn record(&mut self, exercise: &Exercise, passed: bool) {
+        self.update(exercise, |entry| entry.attempt(exercise, passed));
+    }
+
+    /// Record an attempt that says nothing about whether the exercise is
+    /// solved, e.g. running only some of its tests
+    pub fn count_attempt(&mut self, exercise: &Exercise) {
+        self.update(exercise, |entry| entry.attempts += 1);
+    }
+
+    /// Mark the exercise as skipped, so that it comes last among the
+    /// exercises that are not done yet
+    pub fn skip(&mut self, exercise: &Exercise) {
+        self.update(exercise, |entry| entry.skipped = true);
+    }
+
+    /// Reveal the next hint of the exercise, unless all of them are revealed
+    /// already. Returns how many hints are revealed now.
+    pub fn reveal_hint(&mut self, exercise: &Exercise) -> usize {
+        let total = exercise.hints().len();
+        if self.hints_used(exercise) < total {
+            self.update(exercise, |entry| {
+                entry.hints_used = (entry.hints_used + 1).min(total)
+            });
+        }
+        self.hints_used(exercise)
+    }
+
+    /// Remember that the learner looked at the solution of an exercise
+    /// they didn't finish
+    pub fn give_up(&mut self, exercise: &Exercise) {
+        self.update(exercise, |entry| entry.gave_up = true);
+    }
+
+    pub fn attempts(&self, exercise: &Exercise) -> u32 {
+        self.get(&exercise.name).map_or(0, |p| p.attempts)
+    }
+
+    pub fn hints_used(&self, exercise: &Exercise) -> usize {
+        self.get(&exercise.name).map_or(0, |p| p.hints_used)
+    }
+
+    fn update(&mut self, exercise: &Exercise, change: impl FnOnce(&mut ExerciseProgress)) {
+        // Pick up what other rustlings processes (e.g. a `run` in another
+        // terminal while `watch` is active) wrote in the meantime
+        if let Ok(on_disk) = Self::read() {
+            self.exercises = on_disk.exercises;
+        }
+
+        change(self.exercises.entry(exercise.name.clone()).or_default());
+
+        if let Err(e) = self.save() {
+            warn!("Could not save your progress: {}", e);
+        }
+    }
+
+    /// Whether the current source of the exercise was seen compiling and passing
+    pub fn is_verified(&self, exercise: &Exercise) -> bool {
+        match self
+            .get(&exercise.name)
+            .and_then(|p| p.source_hash.as_ref())
+        {
+            Some(hash) => *hash == exercise.source_hash(),
+            None => false,
+        }
+    }
+
+    /// An exercise is done once its current source was verified
+    /// and the `I AM NOT DONE` comment has been removed
+    pub fn is_done(&self, exercise: &Exercise) -> bool {
+        exercise.looks_done() && self.is_verified(exercise)
+    }
+
+    /// Skipped exercises stay skipped until they are done
+    pub fn is_skipped(&self, exercise: &Exercise) -> bool {
+        self.get(&exercise.name).is_some_and(|p| p.skipped) && !self.is_done(exercise)
+    }
+
+    /// The exercises that are not done yet, in the order to work on them:
+    /// skipped exercises come after all the others
+    pub fn pending<'a>(&self, exercises: &'a [Exercise]) -> Vec<&'a Exercise> {
+        self.pending_from(exercises, 0)
+    }
+
+    /// Like `pending`, but in the order the exercises come up after the
+    /// given one, wrapping around to the first exercise
+    pub fn pending_after<'a>(
+        &self,
+        exercises: &'a [Exercise],
+        exercise: &Exercise,
+    ) -> Vec<&'a Exercise> {
+        let start = exercises
+            .iter()
+            .position(|e| e.name == exercise.name)
+            .map_or(0, |i| i + 1);
+        self.pending_from(exercises, start)
+    }
+
+    fn pending_from<'a>(&self, exercises: &'a [Exercise], start: usize) -> Vec<&'a Exercise> {
+        let (skipped, mut pending): (Vec<&Exercise>, Vec<&Exercise>) = exercises[start..]
+            .iter()
+            .chain(&exercises[..start])
+            .filter(|e| !self.is_done(e))
+            .partition(|e| self.is_skipped(e));
+        pending.extend(skipped);
+        pending
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+    use std::path::PathBuf;
+
+    fn exercise(name: &str) -> Exercise {
+        Exercise {
+            name: name.into(),
+            path: PathBuf::from(format!("tests/fixture/state/{}.rs", name)),
+            mode: Mode::Compile,
+            ..Default::default()
+        }
+    }
+
+    fn verified(exercise: &Exercise) -> ExerciseProgress {
+        ExerciseProgress {
+            attempts: 1,
+            last_success: Some(now()),
+            source_hash: Some(exercise.source_hash()),
+            skipped: false,
+            hints_used: 0,
+            gave_up: false,
+        }
+    }
+
+    #[test]
+    fn test_unverified_exercise_is_not_done() {
+        let store = ProgressStore::default();
+        let finished = exercise("finished_exercise");
+
+        assert!(finished.looks_done());
+        assert!(!store.is_done(&finished));
+    }
+
+    #[test]
+    fn test_verified_exercise_is_done() {
+        let finished = exercise("finished_exercise");
+        let mut store = ProgressStore::default();
+        store
+            .exercises
+            .insert(finished.name.clone(), verified(&finished));
+
+        assert!(store.is_done(&finished));
+    }
+
+    #[test]
+    fn test_verified_exercise_with_marker_is_not_done() {
+        let pending = exercise("pending_exercise");
+        let mut store = ProgressStore::default();
+        store
+            .exercises
+            .insert(pending.name.clone(), verified(&pending));
+
+        assert!(store.is_verified(&pending));
+        assert!(!store.is_done(&pending));
+    }
+
+    #[test]
+    fn test_changed_source_is_not_verified() {
+        let finished = exercise("finished_exercise");
+        let mut store = ProgressStore::default();
+        store.exercises.insert(
+            finished.name.clone(),
+            ExerciseProgress {
+                source_hash: Some("outdated".to_string()),
+                ..verified(&finished)
+            },
+        );
+
+        assert!(!store.is_verified(&finished));
+    }
+
+    #[test]
+    fn test_failed_attempt_is_not_verified() {
+        let finished = exercise("finished_exercise");
+        let mut progress = verified(&finished);
+        progress.attempt(&finished, false);
+        let mut store = ProgressStore::default();
+        store.exercises.insert(finished.name.clone(), progress);
+
+        assert_eq!(store.attempts(&finished), 2);
+        assert!(!store.is_verified(&finished));
+        assert!(!store.is_done(&finished));
+    }
+
+    #[test]
+    fn test_skipped_exercises_are_pending_last() {
+        let exercises = vec![
+            exercise("pending_exercise"),
+            exercise("pending_test_exercise"),
+            exercise("finished_exercise"),
+        ];
+        let mut store = ProgressStore::default();
+        store.exercises.insert(
+            exercises[0].name.clone(),
+            ExerciseProgress {
+                skipped: true,
+                ..Default::default()
+            },
+        );
+        store.exercises.insert(
+            exercises[2].name.clone(),
+            ExerciseProgress {
+                skipped: true,
+                ..verified(&exercises[2])
+            },
+        );
+
+        assert!(store.is_skipped(&exercises[0]));
+        assert!(!store.is_skipped(&exercises[2]));
+        let pending: Vec<&str> = store
+            .pending(&exercises)
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(pending, vec!["pending_test_exercise", "pending_exercise"]);
+    }
+
+    #[test]
+    fn test_pending_after_wraps_around() {
+        let exercises = vec![
+            exercise("pending_exercise"),
+            exercise("finished_exercise"),
+            exercise("pending_test_exercise"),
+        ];
+        let mut store = ProgressStore::default();
+        store
+            .exercises
+            .insert(exercises[1].name.clone(), verified(&exercises[1]));
+
+        let names = |after: &Exercise| {
+            store
+                .pending_after(&exercises, after)
+                .iter()
+                .map(|e| e.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(&exercises[1]),
+            vec!["pending_test_exercise", "pending_exercise"]
+        );
+        assert_eq!(
+            names(&exercises[2]),
+            vec!["pending_exercise", "pending_test_exercise"]
+        );
+    }
+}
@@ -1,6 +1,8 @@
-use glob::glob;
+use crate::exercise::{self, Exercise};
+use crate::reset::normalize;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
+use std::path::Path;
 use std::process::Command;
 
 /// Contains the structure of resulting rust-project.json file
@@ -38,7 +40,7 @@ impl RustAnalyzerProject {
 
     /// If path contains .rs extension, add a crate to `rust-project.json`
     fn path_to_json(&mut self, path: String) {
-        if let Some((_, ext)) = path.split_once('.') {
+        if let Some(ext) = Path::new(&path).extension() {
             if ext == "rs" {
                 self.crates.push(Crate {
                     root_module: path,
@@ -51,17 +53,40 @@ impl RustAnalyzerProject {
         }
     }
 
-    /// Parse the exercises folder for .rs files, any matches will create
-    /// a new `crate` in rust-project.json which allows rust-analyzer to
-    /// treat it like a normal binary
-    pub fn exercies_to_json(&mut self) -> Result<(), Box<dyn Error>> {
-        for e in glob("./exercises/**/*")? {
-            let path = e?.to_string_lossy().to_string();
-            self.path_to_json(path);
+    /// Parse the directories of the exercises for .rs files, any matches
+    /// will create a new `crate` in rust-project.json which allows
+    /// rust-analyzer to treat it like a normal binary.
+    /// The files of Cargo exercises are skipped, as rust-analyzer
+    /// loads those from their own Cargo.toml
+    pub fn exercies_to_json(&mut self, exercises: &[Exercise]) -> Result<(), Box<dyn Error>> {
+        let cargo_exercises: Vec<&Exercise> = exercises.iter().filter(|e| e.is_cargo()).collect();
+        for path in exercise::rust_files(&exercise::roots(exercises)) {
+            if cargo_exercises
+                .iter()
+                .any(|exercise| normalize(&path).starts_with(normalize(&exercise.path)))
+            {
+                continue;
+            }
+            self.path_to_json(path.to_string_lossy().to_string());
         }
         Ok(())
     }
 
+    /// The projects rust-analyzer has to be pointed at through its
+    /// `linkedProjects` option: rust-project.json for the single file
+    /// exercises, and the Cargo.toml of every Cargo exercise
+    pub fn linked_projects(&self, cargo_exercises: &[&Exercise]) -> String {
+        let projects: Vec<String> = std::iter::once(Path::new("rust-project.json").to_path_buf())
+            .chain(
+                cargo_exercises
+                    .iter()
+                    .map(|e| normalize(&e.manifest_path())),
+            )
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        serde_json::to_string_pretty(&projects).expect("Failed to serialize to JSON")
+    }
+
     /// Use `rustc` to determine the default toolchain
     pub fn get_sysroot_src(&mut self) -> Result<(), Box<dyn Error>> {
         let toolchain = Command::new("rustc")
@@ -0,0 +1,243 @@
+use crate::exercise::Mode;
+use crate::verify::{Record, Verdict};
+use console::strip_ansi_codes;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+// The kinds of reports `rustlings verify --report` can write
+#[derive(PartialEq, Debug)]
+pub enum Format {
+    // JUnit XML, which CI systems understand
+    Junit,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "junit" => Ok(Format::Junit),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "unknown report format {}, pick one of junit and json",
+                format
+            )),
+        }
+    }
+}
+
+// Write a report of how verifying every exercise went to the given file
+pub fn write(records: &[Record], format: &Format, path: &Path) -> io::Result<()> {
+    let report = match format {
+        Format::Junit => junit(records),
+        Format::Json => json(records),
+    };
+    fs::write(path, report)
+}
+
+fn message(record: &Record) -> String {
+    let exercise = record.exercise;
+    match record.verdict {
+        Verdict::Done => format!("{} is done", exercise),
+        Verdict::NotDone => format!("{} still has the `I AM NOT DONE` comment", exercise),
+        Verdict::CompileFailed => format!("Compiling of {} failed", exercise),
+        Verdict::RunFailed => format!("Running or testing {} failed", exercise),
+        Verdict::TimedOut => format!("Running {} timed out", exercise),
+        Verdict::WrongOutput => format!("{} didn't print the expected output", exercise),
+        Verdict::Tampered => format!("The tests of {} were changed", exercise),
+        Verdict::BrokenRules => format!("{} doesn't follow the rules of the exercise", exercise),
+        Verdict::NotRun => format!("{} wasn't verified, an earlier exercise failed", exercise),
+    }
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    exercises: Vec<JsonExercise<'a>>,
+    summary: Summary,
+}
+
+#[derive(Serialize)]
+struct JsonExercise<'a> {
+    name: &'a str,
+    path: String,
+    mode: Mode,
+    status: &'static str,
+    // In seconds
+    duration: f64,
+    output: String,
+}
+
+#[derive(Serialize)]
+struct Summary {
+    total: usize,
+    done: usize,
+    failed: usize,
+    not_run: usize,
+    // In seconds
+    duration: f64,
+}
+
+impl Summary {
+    fn new(records: &[Record]) -> Summary {
+        let count = |counted: &dyn Fn(Verdict) -> bool| {
+            records.iter().filter(|r| counted(r.verdict)).count()
+        };
+        Summary {
+            total: records.len(),
+            done: count(&|v| v == Verdict::Done),
+            failed: count(&|v| v != Verdict::Done && v != Verdict::NotRun),
+            not_run: count(&|v| v == Verdict::NotRun),
+            duration: records.iter().map(|r| r.duration.as_secs_f64()).sum(),
+        }
+    }
+}
+
+fn json(records: &[Record]) -> String {
+    let report = JsonReport {
+        exercises: records
+            .iter()
+            .map(|record| JsonExercise {
+                name: &record.exercise.name,
+                path: record.exercise.path.display().to_string(),
+                mode: record.exercise.mode,
+                status: record.verdict.name(),
+                duration: record.duration.as_secs_f64(),
+                output: strip_ansi_codes(&record.output).to_string(),
+            })
+            .collect(),
+        summary: Summary::new(records),
+    };
+    serde_json::to_string_pretty(&report).expect("Failed to serialize to JSON")
+}
+
+// Every exercise is a test case, named after the exercise and grouped by
+// topic. Exercises that are not done count as failures, while the ones
+// that weren't verified are skipped.
+fn junit(records: &[Record]) -> String {
+    let summary = Summary::new(records);
+    let counts = format!(
+        "tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\"",
+        summary.total, summary.failed, summary.not_run, summary.duration
+    );
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!("<testsuites name=\"rustlings\" {}>\n", counts));
+    xml.push_str(&format!("  <testsuite name=\"rustlings\" {}>\n", counts));
+    for record in records {
+        let exercise = record.exercise;
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\" time=\"{:.3}\">\n",
+            escape(&exercise.name),
+            escape(&exercise.topic()),
+            escape(&exercise.path.display().to_string()),
+            record.duration.as_secs_f64()
+        ));
+        xml.push_str(&format!(
+            "      <properties><property name=\"mode\" value=\"{}\"/></properties>\n",
+            format!("{:?}", exercise.mode).to_lowercase()
+        ));
+        match record.verdict {
+            Verdict::Done => {}
+            Verdict::NotRun => xml.push_str(&format!(
+                "      <skipped message=\"{}\"/>\n",
+                escape(&message(record))
+            )),
+            verdict => xml.push_str(&format!(
+                "      <failure type=\"{}\" message=\"{}\"/>\n",
+                verdict.name(),
+                escape(&message(record))
+            )),
+        }
+        if !record.output.trim().is_empty() {
+            xml.push_str(&format!(
+                "      <system-out>{}</system-out>\n",
+                escape(&strip_ansi_codes(&record.output))
+            ));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+// Make the text safe to put in an XML attribute or element. Control
+// characters other than whitespace aren't allowed in XML at all.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' | '\r' | '\t' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Exercise;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    #[test]
+    fn test_junit_report() {
+        let exercise = |name: &str| Exercise {
+            name: name.into(),
+            path: PathBuf::from(format!("exercises/intro/{}.rs", name)),
+            mode: Mode::Compile,
+            ..Default::default()
+        };
+        let (intro1, intro2, intro3) = (exercise("intro1"), exercise("intro2"), exercise("intro3"));
+        let records = vec![
+            Record {
+                exercise: &intro1,
+                verdict: Verdict::Done,
+                duration: Duration::from_millis(1500),
+                output: String::new(),
+            },
+            Record {
+                exercise: &intro2,
+                verdict: Verdict::CompileFailed,
+                duration: Duration::from_millis(250),
+                output: "\u{1b}[31merror\u{1b}[0m: expected `<T>` & \"more\"".into(),
+            },
+            Record {
+                exercise: &intro3,
+                verdict: Verdict::NotRun,
+                duration: Duration::ZERO,
+                output: String::new(),
+            },
+        ];
+        assert_eq!(
+            junit(&records),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="rustlings" tests="3" failures="1" skipped="1" time="1.750">
+  <testsuite name="rustlings" tests="3" failures="1" skipped="1" time="1.750">
+    <testcase name="intro1" classname="intro" file="exercises/intro/intro1.rs" time="1.500">
+      <properties><property name="mode" value="compile"/></properties>
+    </testcase>
+    <testcase name="intro2" classname="intro" file="exercises/intro/intro2.rs" time="0.250">
+      <properties><property name="mode" value="compile"/></properties>
+      <failure type="compile_failed" message="Compiling of exercises/intro/intro2.rs failed"/>
+      <system-out>error: expected `&lt;T&gt;` &amp; &quot;more&quot;</system-out>
+    </testcase>
+    <testcase name="intro3" classname="intro" file="exercises/intro/intro3.rs" time="0.000">
+      <properties><property name="mode" value="compile"/></properties>
+      <skipped message="exercises/intro/intro3.rs wasn&apos;t verified, an earlier exercise failed"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use crate::diff::print_diff;
+use crate::exercise::Exercise;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+const ORIGINALS_DIR: &str = ".rustlings-originals";
+
+// Strip `./`, so that paths can be compared by their components. The paths
+// of the exercises have no `..` or root, `lint::errors` rejects those, so
+// the copies of two files never end up in the same place.
+pub fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect()
+}
+
+// Where the pristine copy of the given exercise file is kept
+fn original_path(file: &Path) -> PathBuf {
+    Path::new(ORIGINALS_DIR).join(normalize(file))
+}
+
+// The pristine contents of the given exercise file
+pub fn original(file: &Path) -> Option<String> {
+    fs::read_to_string(original_path(file)).ok()
+}
+
+// Keep a copy of every exercise the first time rustlings sees it,
+// so that it can later be restored with `rustlings reset`
+pub fn store_originals(exercises: &[Exercise]) -> io::Result<()> {
+    for file in exercises.iter().flat_map(Exercise::source_files) {
+        let original = original_path(&file);
+        if original.exists() || !file.exists() {
+            continue;
+        }
+        if let Some(parent) = original.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&file, &original)?;
+    }
+    Ok(())
+}
+
+// Pick the exercises to reset: either the exercise with the given name,
+// or all exercises inside of the given directory
+pub fn select<'a>(target: &str, exercises: &'a [Exercise]) -> Vec<&'a Exercise> {
+    if let Some(exercise) = exercises.iter().find(|e| e.name == target) {
+        return vec![exercise];
+    }
+    let dir = normalize(Path::new(target));
+    exercises
+        .iter()
+        .filter(|e| normalize(&e.path).starts_with(&dir))
+        .collect()
+}
+
+// Restore the given exercises to their original contents.
+// Unless `force` is set, nothing is overwritten and only the changes
+// that would be lost are shown.
+pub fn reset(exercises: &[&Exercise], force: bool) -> Result<(), ()> {
+    let mut changed = 0;
+    for file in exercises.iter().flat_map(|e| e.source_files()) {
+        let original = match fs::read_to_string(original_path(&file)) {
+            Ok(original) => original,
+            Err(_) => {
+                warn!(
+                    "No original copy of {} was found, skipping it",
+                    file.display()
+                );
+                continue;
+            }
+        };
+        let current = fs::read_to_string(&file).unwrap_or_default();
+        if current == original {
+            continue;
+        }
+        changed += 1;
+
+        if force {
+            if let Err(e) = fs::write(&file, &original) {
+                warn!("Failed to reset {}", file.display());
+                println!("{}", e);
+                return Err(());
+            }
+            success!("Reset {} to its original contents", file.display());
+        } else {
+            warn!("Resetting {} would lose these changes:", file.display());
+            print_diff(&current, &original);
+            println!();
+        }
+    }
+
+    if changed == 0 {
+        println!("Nothing to reset, the exercises are unchanged.");
+    } else if !force {
+        println!("Run the same command with `--force` to reset anyway.");
+        return Err(());
+    }
+    Ok(())
+}
+
+// Restore the files of the given exercise to their original contents
+// without showing or asking anything. Returns how many files changed.
+pub fn restore(exercise: &Exercise) -> io::Result<usize> {
+    let mut changed = 0;
+    for file in exercise.source_files() {
+        let Ok(original) = fs::read_to_string(original_path(&file)) else {
+            continue;
+        };
+        if fs::read_to_string(&file).ok().as_ref() != Some(&original) {
+            fs::write(&file, &original)?;
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    fn exercise(name: &str, path: &str) -> Exercise {
+        Exercise {
+            name: name.into(),
+            path: PathBuf::from(path),
+            mode: Mode::Compile,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_select_by_name_or_directory() {
+        let exercises = vec![
+            exercise("vec1", "exercises/collections/vec1.rs"),
+            exercise("vec2", "exercises/collections/vec2.rs"),
+            exercise("if1", "exercises/if/if1.rs"),
+        ];
+
+        let names = |target| {
+            select(target, &exercises)
+                .iter()
+                .map(|e| e.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("vec2"), vec!["vec2"]);
+        assert_eq!(names("./exercises/collections/"), vec!["vec1", "vec2"]);
+        assert_eq!(names("exercises"), vec!["vec1", "vec2", "if1"]);
+        assert!(names("exercises/coll").is_empty());
+    }
+
+    #[test]
+    fn test_original_path_mirrors_exercise_path() {
+        let vec1 = exercise("vec1", "./exercises/collections/vec1.rs");
+        assert_eq!(
+            original_path(&vec1.path),
+            Path::new(ORIGINALS_DIR).join("exercises/collections/vec1.rs")
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use crate::exercise::Exercise;
+use crate::tamper::is_cfg_test;
+use serde::Deserialize;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::Path;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Expr, Token};
+
+// Something an exercise can `require` or `forbid` in info.toml: a function
+// or method by its name, e.g. `map`, a macro, e.g. `println!`, or one of
+// the keywords `for`, `while`, `loop`, `match` and `unsafe`, or `?`
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(try_from = "String")]
+pub enum Construct {
+    Call(String),
+    Macro(String),
+    For,
+    While,
+    Loop,
+    Match,
+    Unsafe,
+    Try,
+}
+
+impl TryFrom<String> for Construct {
+    type Error = String;
+
+    fn try_from(construct: String) -> Result<Self, Self::Error> {
+        let is_name = |name: &str| syn::parse_str::<syn::Ident>(name).is_ok();
+        match construct.as_str() {
+            "for" => Ok(Construct::For),
+            "while" => Ok(Construct::While),
+            "loop" => Ok(Construct::Loop),
+            "match" => Ok(Construct::Match),
+            "unsafe" => Ok(Construct::Unsafe),
+            "?" => Ok(Construct::Try),
+            name if is_name(name) => Ok(Construct::Call(construct)),
+            name if name.strip_suffix('!').is_some_and(is_name) => {
+                Ok(Construct::Macro(name.trim_end_matches('!').to_string()))
+            }
+            _ => Err(format!(
+                "`{}` is neither a function, a macro, nor one of for, while, loop, match, unsafe and ?",
+                construct
+            )),
+        }
+    }
+}
+
+impl Display for Construct {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Construct::Call(name) => write!(f, "`{}`", name),
+            Construct::Macro(name) => write!(f, "`{}!`", name),
+            Construct::For => write!(f, "`for` loops"),
+            Construct::While => write!(f, "`while` loops"),
+            Construct::Loop => write!(f, "`loop`"),
+            Construct::Match => write!(f, "`match`"),
+            Construct::Unsafe => write!(f, "`unsafe`"),
+            Construct::Try => write!(f, "the `?` operator"),
+        }
+    }
+}
+
+// Every construct of the source, with the line it is first used on.
+// The test module doesn't count, it isn't the learner's code.
+fn constructs(source: &str) -> syn::Result<Vec<(Construct, usize)>> {
+    let file = syn::parse_file(source)?;
+    let mut finder = Finder::default();
+    finder.visit_file(&file);
+    Ok(finder.found)
+}
+
+#[derive(Default)]
+struct Finder {
+    found: Vec<(Construct, usize)>,
+}
+
+impl Finder {
+    fn add(&mut self, construct: Construct, spanned: &impl Spanned) {
+        if !self.found.iter().any(|(found, _)| *found == construct) {
+            self.found.push((construct, spanned.span().start().line));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Finder {
+    fn visit_item_mod(&mut self, module: &'ast syn::ItemMod) {
+        if !module.attrs.iter().any(is_cfg_test) {
+            visit::visit_item_mod(self, module);
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        self.add(Construct::Call(call.method.to_string()), &call.method);
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let Expr::Path(path) = &*call.func {
+            if let Some(last) = path.path.segments.last() {
+                self.add(Construct::Call(last.ident.to_string()), &last.ident);
+            }
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    // The arguments of most macros, like `println!` and `vec!`, are
+    // expressions, so whatever they use counts too
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if let Some(last) = mac.path.segments.last() {
+            self.add(Construct::Macro(last.ident.to_string()), &last.ident);
+        }
+        if let Ok(args) = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+        visit::visit_macro(self, mac);
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast syn::ExprForLoop) {
+        self.add(Construct::For, &expr.for_token);
+        visit::visit_expr_for_loop(self, expr);
+    }
+
+    fn visit_expr_while(&mut self, expr: &'ast syn::ExprWhile) {
+        self.add(Construct::While, &expr.while_token);
+        visit::visit_expr_while(self, expr);
+    }
+
+    fn visit_expr_loop(&mut self, expr: &'ast syn::ExprLoop) {
+        self.add(Construct::Loop, &expr.loop_token);
+        visit::visit_expr_loop(self, expr);
+    }
+
+    fn visit_expr_match(&mut self, expr: &'ast syn::ExprMatch) {
+        self.add(Construct::Match, &expr.match_token);
+        visit::visit_expr_match(self, expr);
+    }
+
+    fn visit_expr_unsafe(&mut self, expr: &'ast syn::ExprUnsafe) {
+        self.add(Construct::Unsafe, &expr.unsafe_token);
+        visit::visit_expr_unsafe(self, expr);
+    }
+
+    fn visit_signature(&mut self, signature: &'ast syn::Signature) {
+        if let Some(unsafety) = &signature.unsafety {
+            self.add(Construct::Unsafe, unsafety);
+        }
+        visit::visit_signature(self, signature);
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+        if let Some(unsafety) = &item.unsafety {
+            self.add(Construct::Unsafe, unsafety);
+        }
+        visit::visit_item_impl(self, item);
+    }
+
+    fn visit_expr_try(&mut self, expr: &'ast syn::ExprTry) {
+        self.add(Construct::Try, &expr.question_token);
+        visit::visit_expr_try(self, expr);
+    }
+}
+
+// The rules of the exercise its source breaks, e.g. a required function
+// that is never called, or a forbidden keyword. The source files of a
+// Cargo exercise are taken together.
+pub fn check(exercise: &Exercise) -> Vec<String> {
+    if exercise.require.is_empty() && exercise.forbid.is_empty() {
+        return Vec::new();
+    }
+    let mut found: Vec<(Construct, usize, &Path)> = Vec::new();
+    let files = exercise.source_files();
+    for file in files
+        .iter()
+        .filter(|file| file.extension() == Some("rs".as_ref()))
+    {
+        let source = fs::read_to_string(file).unwrap_or_default();
+        // It compiled, so it parses, unless it's newer Rust than syn knows
+        let Ok(constructs) = constructs(&source) else {
+            continue;
+        };
+        found.extend(
+            constructs
+                .into_iter()
+                .map(|(c, line)| (c, line, file.as_path())),
+        );
+    }
+
+    let mut broken = Vec::new();
+    for required in &exercise.require {
+        if !found.iter().any(|(construct, ..)| construct == required) {
+            broken.push(format!("it has to use {}", required));
+        }
+    }
+    for forbidden in &exercise.forbid {
+        if let Some((_, line, file)) = found.iter().find(|(construct, ..)| construct == forbidden) {
+            broken.push(format!(
+                "it must not use {}, but does on line {} of {}",
+                forbidden,
+                line,
+                file.display()
+            ));
+        }
+    }
+    broken
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn names(source: &str) -> Vec<String> {
+        constructs(source)
+            .unwrap()
+            .into_iter()
+            .map(|(construct, line)| format!("{} {}", construct, line))
+            .collect()
+    }
+
+    #[test]
+    fn test_constructs_are_found_with_their_line() {
+        let source = r#"
+fn total(numbers: &[&str]) -> Result<i32, std::num::ParseIntError> {
+    let mut total = 0;
+    for n in numbers {
+        total += n.parse::<i32>()?;
+    }
+    println!("{}", numbers.iter().map(|n| n.len()).sum::<usize>());
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn adds() {
+        assert_eq!(super::total(&["1"]).unwrap(), 1);
+    }
+}
+"#;
+        assert_eq!(
+            names(source),
+            vec![
+                "`for` loops 4",
+                "the `?` operator 5",
+                "`parse` 5",
+                "`println!` 7",
+                "`sum` 7",
+                "`map` 7",
+                "`iter` 7",
+                "`len` 7",
+                "`Ok` 8",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_constructs() {
+        let parse = |construct: &str| Construct::try_from(construct.to_string());
+        assert_eq!(parse("map"), Ok(Construct::Call("map".to_string())));
+        assert_eq!(parse("vec!"), Ok(Construct::Macro("vec".to_string())));
+        assert_eq!(parse("?"), Ok(Construct::Try));
+        assert_eq!(parse("unsafe"), Ok(Construct::Unsafe));
+        assert!(parse("not a name").is_err());
+    }
+}
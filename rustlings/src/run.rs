@@ -1,30 +1,47 @@
+use crate::diagnostics::print_errors;
 use crate::exercise::{Exercise, Mode};
-use crate::verify::test;
+use crate::libtest::{self, print_checklist};
+use crate::progress::ProgressStore;
+use crate::verify::{report_timeout, run_once};
 use indicatif::ProgressBar;
 
 // Invoke the rust compiler on the path of the given exercise,
 // and run the ensuing binary.
 // The verbose argument helps determine whether or not to show
 // the output from the test harnesses (if the mode of the exercise is test)
-pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
-    match exercise.mode {
-        Mode::Test => test(exercise, verbose)?,
-        Mode::Compile => compile_and_run(exercise)?,
-        Mode::Clippy => compile_and_run(exercise)?,
+// and all compiler errors instead of only the first one.
+// With a test filter, only the matching tests of the exercise are run.
+// The attempt is recorded in the given ProgressStore.
+pub fn run(
+    exercise: &Exercise,
+    store: &mut ProgressStore,
+    verbose: bool,
+    test_filter: Option<&str>,
+) -> Result<(), ()> {
+    let result = match (exercise.mode, test_filter) {
+        (Mode::Test | Mode::Cargo, Some(filter)) => run_tests(exercise, filter, verbose),
+        (_, Some(_)) => {
+            warn!("{} has no tests to choose from", exercise);
+            return Err(());
+        }
+        (_, None) => run_once(exercise, verbose),
+    };
+    match (result.is_ok(), test_filter) {
+        // Passing some of the tests doesn't solve the exercise
+        (true, Some(_)) => store.count_attempt(exercise),
+        (passed, _) => store.record(exercise, passed),
     }
-    Ok(())
+    result
 }
 
-// Invoke the rust compiler on the path of the given exercise
-// and run the ensuing binary.
-// This is strictly for non-test binaries, so output is displayed
-fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
+// Compile the given test exercise and run only the tests whose names
+// contain the filter, showing how each of them did
+fn run_tests(exercise: &Exercise, filter: &str, verbose: bool) -> Result<(), ()> {
     let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {}...", exercise));
+    progress_bar.set_message(format!("Testing {}...", exercise));
     progress_bar.enable_steady_tick(100);
 
-    let compilation_result = exercise.compile();
-    let compilation = match compilation_result {
+    let compilation = match exercise.compile() {
         Ok(compilation) => compilation,
         Err(output) => {
             progress_bar.finish_and_clear();
@@ -32,27 +49,28 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
                 "Compilation of {} failed!, Compiler error message:\n",
                 exercise
             );
-            println!("{}", output.stderr);
+            print_errors(&output.diagnostics, &output.stderr, verbose);
             return Err(());
         }
     };
-
-    progress_bar.set_message(format!("Running {}...", exercise));
-    let result = compilation.run();
+    let result = compilation.run_tests(filter);
     progress_bar.finish_and_clear();
 
-    match result {
-        Ok(output) => {
-            println!("{}", output.stdout);
-            success!("Successfully ran {}", exercise);
-            Ok(())
-        }
-        Err(output) => {
-            println!("{}", output.stdout);
-            println!("{}", output.stderr);
-
-            warn!("Ran {} with errors", exercise);
-            Err(())
-        }
+    let output = match &result {
+        Ok(output) | Err(output) => output,
+    };
+    if output.timed_out {
+        report_timeout(exercise, output);
+        return Err(());
+    }
+    if verbose {
+        println!("{}", output.stdout);
+    }
+    let results = libtest::parse(&output.stdout);
+    if results.is_empty() {
+        warn!("No test of this exercise matches '{}'", filter);
+        return Err(());
     }
+    print_checklist(&results);
+    result.map(|_| ()).map_err(|_| ())
 }
@@ -0,0 +1,92 @@
+// A command typed in watch mode
+#[derive(PartialEq, Debug)]
+pub enum ShellCommand {
+    // Print the hint of the current exercise
+    Hint,
+    // Print all compiler errors of the current exercise
+    Errors,
+    // Explain the given error code, or the first error of the current exercise
+    Explain(Option<String>),
+    // Verify the current exercise again
+    Run,
+    // Move on to the next exercise that is not done yet
+    Next,
+    // Mark the current exercise as skipped and move on
+    Skip,
+    // Work on the exercise with the given name
+    Goto(String),
+    // List the exercises that are not done yet
+    List,
+    // Print the path of the current exercise
+    Path,
+    Clear,
+    Quit,
+    Help,
+}
+
+impl ShellCommand {
+    pub fn parse(input: &str) -> Result<ShellCommand, String> {
+        let mut words = input.split_whitespace();
+        let command = match words.next() {
+            Some("hint") => ShellCommand::Hint,
+            Some("errors") => ShellCommand::Errors,
+            Some("explain") => ShellCommand::Explain(words.next().map(str::to_string)),
+            Some("run") => ShellCommand::Run,
+            Some("next") => ShellCommand::Next,
+            Some("skip") => ShellCommand::Skip,
+            Some("goto") => match words.next() {
+                Some(name) => ShellCommand::Goto(name.to_string()),
+                None => return Err("goto needs the name of an exercise".to_string()),
+            },
+            Some("list") => ShellCommand::List,
+            Some("path") => ShellCommand::Path,
+            Some("clear") => ShellCommand::Clear,
+            Some("quit") => ShellCommand::Quit,
+            Some("help") => ShellCommand::Help,
+            _ => return Err(format!("unknown command: {}", input.trim())),
+        };
+        match words.next() {
+            Some(_) => Err(format!("too many arguments: {}", input.trim())),
+            None => Ok(command),
+        }
+    }
+}
+
+pub const HELP: &str = "Commands available to you in watch mode:
+  hint         - prints the current exercise's hint
+  errors       - prints all compiler errors of the current exercise
+  explain      - explains the first compiler error in detail
+  run          - checks the current exercise again
+  next         - moves on to the next exercise that is not done yet
+  skip         - skips the current exercise, you can come back to it later
+  goto NAME    - switches to the exercise with the given name
+  list         - lists the exercises that are not done yet
+  path         - prints the path of the current exercise
+  clear        - clears the screen
+  quit         - quits watch mode
+  help         - displays this help message";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(ShellCommand::parse("hint\n"), Ok(ShellCommand::Hint));
+        assert_eq!(
+            ShellCommand::parse(" goto  vecs2 "),
+            Ok(ShellCommand::Goto("vecs2".to_string()))
+        );
+        assert_eq!(
+            ShellCommand::parse("explain E0308"),
+            Ok(ShellCommand::Explain(Some("E0308".to_string())))
+        );
+        assert_eq!(
+            ShellCommand::parse("explain"),
+            Ok(ShellCommand::Explain(None))
+        );
+        assert!(ShellCommand::parse("goto").is_err());
+        assert!(ShellCommand::parse("next vecs2").is_err());
+        assert!(ShellCommand::parse("jump").is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::diff::side_by_side;
+use crate::exercise::Exercise;
+use crate::progress::ProgressStore;
+use console::{style, Term};
+use std::fs;
+
+// Used when the width of the terminal can't be found out, e.g. when piped
+const DEFAULT_WIDTH: usize = 120;
+
+// Show the reference solution of the given exercise next to the learner's
+// version. The solution is only revealed once the exercise is done,
+// unless the learner gives up, which is recorded in the ProgressStore.
+pub fn solution(exercise: &Exercise, store: &mut ProgressStore, give_up: bool) -> Result<(), ()> {
+    let files = exercise.solution_files();
+    if files.is_empty() {
+        warn!("{} has no reference solution", exercise);
+        return Err(());
+    }
+    if !store.is_done(exercise) {
+        if !give_up {
+            warn!("Finish {} first to see its solution", exercise);
+            println!("If you are stuck, `rustlings hint` reveals another hint.");
+            println!("Add `--give-up` to see the solution anyway, this will be remembered.");
+            return Err(());
+        }
+        store.give_up(exercise);
+    }
+
+    let width = match Term::stdout().size_checked() {
+        Some((_, columns)) => columns as usize,
+        None => DEFAULT_WIDTH,
+    };
+    for (file, solution) in files {
+        let (yours, theirs) = match (fs::read_to_string(&file), fs::read_to_string(&solution)) {
+            (Ok(yours), Ok(theirs)) => (yours, theirs),
+            (Err(e), _) | (_, Err(e)) => {
+                warn!("Could not compare {} with its solution", file.display());
+                println!("{}", e);
+                return Err(());
+            }
+        };
+        let column = width.saturating_sub(3) / 2;
+        println!(
+            "{:<column$} │ {}",
+            style(file.display()).bold(),
+            style(solution.display()).bold(),
+            column = column
+        );
+        print!("{}", side_by_side(&yours, &theirs, width));
+        println!();
+    }
+    Ok(())
+}
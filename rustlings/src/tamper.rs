@@ -0,0 +1,225 @@
+use crate::exercise::{Exercise, Mode};
+use crate::progress::ProgressStore;
+use crate::reset;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use syn::{Attribute, Item};
+
+// The fingerprints of the tests every exercise came with, by exercise name,
+// or nothing for exercises that came without a test module or that couldn't
+// be parsed. They are kept apart from the originals for `rustlings reset`,
+// so that losing those doesn't lose track of the tests.
+const FINGERPRINTS_PATH: &str = ".rustlings-fingerprints.json";
+
+// What the tests of an exercise look like. Only their tokens count,
+// so reformatting them or changing their comments doesn't matter.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Fingerprint {
+    // The hash of every `#[test]` function, by its name
+    tests: BTreeMap<String, String>,
+    // The hash of everything else in the test module, e.g. helpers
+    helpers: String,
+}
+
+// The fingerprint of the `#[cfg(test)]` module of the given source,
+// if it has one
+pub fn fingerprint(source: &str) -> syn::Result<Option<Fingerprint>> {
+    let file = syn::parse_file(source)?;
+    let module = file.items.into_iter().find_map(|item| match item {
+        Item::Mod(module) if module.attrs.iter().any(is_cfg_test) => Some(module),
+        _ => None,
+    });
+    let Some(module) = module else {
+        return Ok(None);
+    };
+
+    let mut tests = BTreeMap::new();
+    let mut helpers = Sha256::new();
+    for item in module.content.map(|(_, items)| items).unwrap_or_default() {
+        match item {
+            Item::Fn(test) if test.attrs.iter().any(|a| a.path().is_ident("test")) => {
+                tests.insert(test.sig.ident.to_string(), hash(&test));
+            }
+            helper => helpers.update(helper.to_token_stream().to_string()),
+        }
+    }
+    Ok(Some(Fingerprint {
+        tests,
+        helpers: format!("{:x}", helpers.finalize()),
+    }))
+}
+
+pub fn is_cfg_test(attribute: &Attribute) -> bool {
+    attribute.path().is_ident("cfg")
+        && attribute
+            .parse_args::<syn::Ident>()
+            .is_ok_and(|arg| arg == "test")
+}
+
+fn hash(tokens: &impl ToTokens) -> String {
+    format!("{:x}", Sha256::digest(tokens.to_token_stream().to_string()))
+}
+
+// Whether the tests of the exercise are protected from changes. Exercises
+// can let learners edit their tests with `editable_tests = true`.
+pub fn is_protected(exercise: &Exercise) -> bool {
+    matches!(exercise.mode, Mode::Test) && !exercise.editable_tests
+}
+
+fn load() -> BTreeMap<String, Option<Fingerprint>> {
+    fs::read_to_string(FINGERPRINTS_PATH)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+// Record the fingerprint of the tests of every protected exercise the first
+// time it is seen, from the copy `rustlings reset` keeps of it. Returns
+// warnings about exercises that were worked on before without a fingerprint,
+// since changes made to their tests back then can't be found anymore, and
+// about tests that couldn't be parsed. They are left to the caller to show,
+// which may be the full-screen interface.
+pub fn record(exercises: &[Exercise], store: &ProgressStore) -> io::Result<Vec<String>> {
+    let mut fingerprints = load();
+    let mut recorded = false;
+    let mut warnings = Vec::new();
+    for exercise in exercises.iter().filter(|e| is_protected(e)) {
+        if fingerprints.contains_key(&exercise.name) {
+            continue;
+        }
+        if store.attempts(exercise) > 0 {
+            warnings.push(format!(
+                "The original tests of {} weren't recorded, only later changes to them are found",
+                exercise
+            ));
+        }
+        let source = reset::original(&exercise.path)
+            .or_else(|| fs::read_to_string(&exercise.path).ok())
+            .unwrap_or_default();
+        let fingerprint = fingerprint(&source).unwrap_or_else(|e| {
+            warnings.push(format!(
+                "Could not parse the tests of {}, changes to them won't be found: {}",
+                exercise, e
+            ));
+            None
+        });
+        fingerprints.insert(exercise.name.clone(), fingerprint);
+        recorded = true;
+    }
+    if recorded {
+        let json = serde_json::to_string_pretty(&fingerprints)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(FINGERPRINTS_PATH, json)?;
+    }
+    Ok(warnings)
+}
+
+// How the tests of the exercise differ from the ones it came with.
+// Exercises without a recorded fingerprint were warned about by `record`.
+pub fn check(exercise: &Exercise) -> Vec<String> {
+    if !is_protected(exercise) {
+        return Vec::new();
+    }
+    match load().get(&exercise.name) {
+        Some(Some(original)) => changes(
+            original,
+            &fs::read_to_string(&exercise.path).unwrap_or_default(),
+        ),
+        _ => Vec::new(),
+    }
+}
+
+// How the tests of the current source differ from the ones of the original
+// source. A source that can't be parsed doesn't compile either, so there
+// is nothing to say then.
+pub fn compare(original: &str, current: &str) -> Vec<String> {
+    match fingerprint(original) {
+        Ok(Some(original)) => changes(&original, current),
+        _ => Vec::new(),
+    }
+}
+
+// How the tests of the current source differ from the original ones.
+// Tests may be added, but not removed, renamed or changed.
+fn changes(original: &Fingerprint, current: &str) -> Vec<String> {
+    let current = match fingerprint(current) {
+        Ok(Some(current)) => current,
+        Ok(None) => return vec!["the test module was removed".to_string()],
+        Err(_) => return Vec::new(),
+    };
+
+    let mut changes = Vec::new();
+    for (name, hash) in &original.tests {
+        match current.tests.get(name) {
+            None => changes.push(format!("the test `{}` was removed or renamed", name)),
+            Some(current) if current != hash => {
+                changes.push(format!("the test `{}` was changed", name))
+            }
+            Some(_) => {}
+        }
+    }
+    if current.helpers != original.helpers {
+        changes.push("the test module was changed besides its tests".to_string());
+    }
+    changes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ORIGINAL: &str = r#"
+fn add(a: i32, b: i32) -> i32 {
+    todo!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds() {
+        assert_eq!(add(1, 2), 3);
+    }
+
+    #[test]
+    fn adds_negative_numbers() {
+        assert_eq!(add(-1, -2), -3);
+    }
+}
+"#;
+
+    #[test]
+    fn test_solving_and_reformatting_is_fine() {
+        let solved = ORIGINAL.replace("todo!()", "a + b").replace(
+            "    #[test]\n    fn adds() {",
+            "    // Easy\n    #[test] fn adds() {",
+        );
+        assert!(compare(ORIGINAL, &solved).is_empty());
+    }
+
+    #[test]
+    fn test_changed_tests_are_found() {
+        let weakened = ORIGINAL.replace("add(1, 2), 3", "3, 3");
+        assert_eq!(
+            compare(ORIGINAL, &weakened),
+            vec!["the test `adds` was changed"]
+        );
+
+        let renamed = ORIGINAL.replace("adds_negative_numbers", "adds_negatives");
+        assert_eq!(
+            compare(ORIGINAL, &renamed),
+            vec!["the test `adds_negative_numbers` was removed or renamed"]
+        );
+
+        let without_tests = &ORIGINAL[..ORIGINAL.find("#[cfg(test)]").unwrap()];
+        assert_eq!(
+            compare(ORIGINAL, without_tests),
+            vec!["the test module was removed"]
+        );
+    }
+}
@@ -0,0 +1,712 @@
+use crate::diagnostics;
+use crate::exercise::{Exercise, ExerciseOutput, Mode, State};
+use crate::libtest;
+use crate::progress::ProgressStore;
+use crate::reset;
+use crate::shell::{self, ShellCommand};
+use crate::verify::{
+    broken_rules_details, evaluate, tampering_details, timeout_details, wrong_output_details,
+    Outcome,
+};
+use crate::{change, reload, Change, WatchStatus};
+use ansi_to_tui::IntoText;
+use console::style;
+use indicatif::ProgressBar;
+use notify::DebouncedEvent;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::VecDeque;
+use std::io;
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+// How long to wait for a key press before looking for other events
+const TICK: Duration = Duration::from_millis(100);
+
+const KEYS: &str =
+    "h hint  n next  s skip  r re-run  x reset  e all errors  : command  ↑↓ scroll  q quit";
+
+// Watch mode as a full-screen terminal UI: the current exercise and its
+// output on the right, the topics and how far the learner got on the left.
+// Exercises are compiled on a background thread, so that the UI keeps
+// responding to keys in the meantime.
+pub fn watch(
+    exercises: Vec<Exercise>,
+    store: &mut ProgressStore,
+    verbose: bool,
+    file_events: &Receiver<DebouncedEvent>,
+) -> io::Result<WatchStatus> {
+    let mut terminal = ratatui::init();
+    let result = App::new(exercises, store, verbose).run(&mut terminal, file_events);
+    ratatui::restore();
+    result
+}
+
+// Exercises that live in the same directory form a topic
+struct Topic {
+    name: String,
+    exercises: Vec<usize>,
+}
+
+fn topics(exercises: &[Exercise]) -> Vec<Topic> {
+    let mut topics: Vec<Topic> = Vec::new();
+    for (i, exercise) in exercises.iter().enumerate() {
+        let name = exercise.topic();
+        match topics.last_mut() {
+            Some(topic) if topic.name == name => topic.exercises.push(i),
+            _ => topics.push(Topic {
+                name,
+                exercises: vec![i],
+            }),
+        }
+    }
+    topics
+}
+
+struct App<'a> {
+    exercises: Vec<Exercise>,
+    store: &'a mut ProgressStore,
+    topics: Vec<Topic>,
+    // Whether each exercise is done, kept up to date after every run,
+    // as finding out reads the exercise's files
+    done: Vec<bool>,
+    // The exercise that is shown
+    current: usize,
+    // The exercises to verify once the current one is done
+    queue: VecDeque<usize>,
+    // The name of the exercise being compiled and run in the background, if any
+    running: Option<String>,
+    // An exercise to verify as soon as the running one is finished
+    rerun: Option<usize>,
+    // Where the background threads send the outcomes to
+    outcome_tx: Sender<(String, Outcome)>,
+    outcome_rx: Receiver<(String, Outcome)>,
+    outcome: Option<Outcome>,
+    // The outcome of the current exercise, as shown
+    output: Text<'static>,
+    scroll: u16,
+    show_hint: bool,
+    show_all_errors: bool,
+    confirm_reset: bool,
+    // A short message for the learner, e.g. that an exercise was reset
+    status: String,
+    // The command being typed after pressing `:`, see `ShellCommand`
+    command: Option<String>,
+}
+
+impl<'a> App<'a> {
+    fn new(exercises: Vec<Exercise>, store: &'a mut ProgressStore, verbose: bool) -> Self {
+        let done: Vec<bool> = exercises.iter().map(|e| store.is_done(e)).collect();
+        let (outcome_tx, outcome_rx) = channel();
+        let mut app = App {
+            topics: topics(&exercises),
+            exercises,
+            store,
+            done,
+            current: 0,
+            queue: VecDeque::new(),
+            running: None,
+            rerun: None,
+            outcome_tx,
+            outcome_rx,
+            outcome: None,
+            output: Text::default(),
+            scroll: 0,
+            show_hint: false,
+            show_all_errors: verbose,
+            confirm_reset: false,
+            status: String::new(),
+            command: None,
+        };
+        app.current = app.pending().front().copied().unwrap_or(0);
+        app
+    }
+
+    // The exercises that are not done yet, skipped ones last
+    fn pending(&self) -> VecDeque<usize> {
+        let pending = self.store.pending(&self.exercises);
+        self.indices(&pending).collect()
+    }
+
+    // Like `pending`, but in the order they come up after the given exercise
+    fn pending_after(&self, i: usize) -> VecDeque<usize> {
+        let pending = self
+            .store
+            .pending_after(&self.exercises, &self.exercises[i]);
+        self.indices(&pending).filter(|&j| j != i).collect()
+    }
+
+    fn indices<'b>(&'b self, exercises: &'b [&Exercise]) -> impl Iterator<Item = usize> + 'b {
+        exercises
+            .iter()
+            .filter_map(|&e| self.exercises.iter().position(|x| std::ptr::eq(x, e)))
+    }
+
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        file_events: &Receiver<DebouncedEvent>,
+    ) -> io::Result<WatchStatus> {
+        match self.pending().front() {
+            Some(&first) => self.focus(first),
+            None => return Ok(WatchStatus::Finished),
+        }
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(TICK)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && self.on_key(key) {
+                        return Ok(WatchStatus::Unfinished);
+                    }
+                }
+            }
+            while let Ok(event) = file_events.try_recv() {
+                match change(event) {
+                    Some(Change::File(path)) => self.on_file_changed(&path),
+                    Some(Change::ExerciseList) => self.on_list_changed(),
+                    None => {}
+                }
+            }
+            while let Ok((name, outcome)) = self.outcome_rx.try_recv() {
+                if self.on_outcome(&name, outcome) {
+                    return Ok(WatchStatus::Finished);
+                }
+            }
+        }
+    }
+
+    // Verify the exercise the learner is working on and show only its result.
+    // Once it is done, the exercises after it that are not done yet follow,
+    // unless the learner is just reviewing an exercise finished before.
+    fn focus(&mut self, i: usize) {
+        self.queue = if self.done[i] {
+            VecDeque::new()
+        } else {
+            self.pending_after(i)
+        };
+        self.verify(i);
+    }
+
+    // Compile and run the given exercise in the background
+    fn verify(&mut self, i: usize) {
+        self.current = i;
+        if self.running.is_some() {
+            self.rerun = Some(i);
+            return;
+        }
+        let exercise = self.exercises[i].clone();
+        self.running = Some(exercise.name.clone());
+        let outcome_tx = self.outcome_tx.clone();
+        thread::spawn(move || {
+            let outcome = evaluate(&exercise, &ProgressBar::hidden());
+            let _ = outcome_tx.send((exercise.name, outcome));
+        });
+    }
+
+    // Returns whether all exercises are done
+    fn on_outcome(&mut self, name: &str, outcome: Outcome) -> bool {
+        self.running = None;
+        if let Some(rerun) = self.rerun.take() {
+            self.verify(rerun);
+            return false;
+        }
+        // The exercise may have been removed from info.toml in the meantime
+        let Some(i) = self.exercises.iter().position(|e| e.name == name) else {
+            return false;
+        };
+
+        let exercise = &self.exercises[i];
+        let passed = matches!(outcome, Outcome::Passed(_));
+        self.store.record(exercise, passed);
+        self.done[i] = self.store.is_done(exercise);
+        if self.done[i] {
+            if let Some(next) = self.queue.pop_front() {
+                self.verify(next);
+                return false;
+            }
+            if self.done.iter().all(|&done| done) {
+                return true;
+            }
+        }
+
+        self.current = i;
+        self.outcome = Some(outcome);
+        self.scroll = 0;
+        self.render_outcome();
+        false
+    }
+
+    // Pick up the changes to info.toml, staying with the current exercise
+    // unless it was removed
+    fn on_list_changed(&mut self) {
+        let current = self.exercises[self.current].name.clone();
+        match reload(&mut self.exercises, self.store) {
+            Ok(changes) => {
+                self.topics = topics(&self.exercises);
+                self.done = self
+                    .exercises
+                    .iter()
+                    .map(|e| self.store.is_done(e))
+                    .collect();
+                self.outcome = None;
+                let current = self.exercises.iter().position(|e| e.name == current);
+                let focus = current
+                    .or_else(|| self.pending().front().copied())
+                    .unwrap_or(0);
+                self.focus(focus);
+                self.status = changes.to_string();
+            }
+            Err(e) => self.status = format!("Kept the exercises as they were: {}", e),
+        }
+    }
+
+    // Focus on the exercise that was saved
+    fn on_file_changed(&mut self, path: &Path) {
+        if let Some(changed) = self.exercises.iter().position(|e| e.contains(path)) {
+            self.focus(changed);
+        }
+    }
+
+    // Returns whether the learner wants to quit
+    fn on_key(&mut self, key: KeyEvent) -> bool {
+        if let Some(command) = &mut self.command {
+            match key.code {
+                KeyCode::Char(c) => command.push(c),
+                KeyCode::Backspace => {
+                    command.pop();
+                }
+                KeyCode::Enter => {
+                    let command = self.command.take().unwrap_or_default();
+                    match ShellCommand::parse(&command) {
+                        Ok(command) => return self.execute(command),
+                        Err(e) => self.status = e,
+                    }
+                }
+                KeyCode::Esc => self.command = None,
+                _ => {}
+            }
+            return false;
+        }
+
+        let confirm_reset = std::mem::take(&mut self.confirm_reset);
+        self.status.clear();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return true,
+            KeyCode::Char('h') => return self.execute(ShellCommand::Hint),
+            KeyCode::Char('e') => {
+                self.show_all_errors = !self.show_all_errors;
+                self.render_outcome();
+            }
+            KeyCode::Char('r') => return self.execute(ShellCommand::Run),
+            KeyCode::Char('n') => return self.execute(ShellCommand::Next),
+            KeyCode::Char('s') => return self.execute(ShellCommand::Skip),
+            KeyCode::Char(':') => self.command = Some(String::new()),
+            KeyCode::Char('x') if confirm_reset => {
+                let exercise = &self.exercises[self.current];
+                self.status = match reset::restore(exercise) {
+                    Ok(0) => format!("{} is unchanged, there was nothing to reset.", exercise),
+                    Ok(_) => format!("Reset {} to its original contents.", exercise),
+                    Err(e) => format!("Failed to reset {}: {}", exercise, e),
+                };
+                self.verify(self.current);
+            }
+            KeyCode::Char('x') => {
+                self.confirm_reset = true;
+                self.status = format!(
+                    "Press x again to reset {}, your changes will be lost. Any other key keeps them.",
+                    self.exercises[self.current]
+                );
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            _ => {}
+        }
+        false
+    }
+
+    // Carry out a command of the watch shell, returns whether to quit
+    fn execute(&mut self, command: ShellCommand) -> bool {
+        let exercise = &self.exercises[self.current];
+        match command {
+            ShellCommand::Hint => {
+                // Opening the pane shows the hints revealed so far, every
+                // further press reveals one more, until there are none left
+                let revealed = self.store.hints_used(exercise);
+                if !self.show_hint && revealed > 0 {
+                    self.show_hint = true;
+                } else if revealed < exercise.hints().len() {
+                    self.store.reveal_hint(exercise);
+                    self.show_hint = true;
+                } else {
+                    self.show_hint = !self.show_hint;
+                }
+            }
+            ShellCommand::Errors => {
+                self.show_all_errors = true;
+                self.render_outcome();
+            }
+            ShellCommand::Explain(code) => {
+                let first_code = match &self.outcome {
+                    Some(Outcome::CompileFailed(output)) => {
+                        diagnostics::errors(&output.diagnostics)
+                            .iter()
+                            .find_map(|d| d.code.as_ref())
+                            .map(|code| code.code.clone())
+                    }
+                    _ => None,
+                };
+                match code.or(first_code) {
+                    Some(code) => {
+                        let text = diagnostics::explanation(&code).unwrap_or_else(|e| e);
+                        self.show_text(text);
+                    }
+                    None => {
+                        self.status = format!("{} has no errors to explain.", exercise);
+                    }
+                }
+            }
+            ShellCommand::Run => self.focus(self.current),
+            ShellCommand::Next | ShellCommand::Skip => {
+                if command == ShellCommand::Skip {
+                    self.store.skip(exercise);
+                    self.status = format!(
+                        "Skipped {}, it will come up again once the others are done.",
+                        exercise
+                    );
+                }
+                match self.pending_after(self.current).front() {
+                    Some(&next) => self.focus(next),
+                    None => self.status = "There are no other exercises left to do.".into(),
+                }
+            }
+            ShellCommand::Goto(name) => match self.exercises.iter().position(|e| e.name == name) {
+                Some(i) => self.focus(i),
+                None => self.status = format!("No exercise found for '{}'!", name),
+            },
+            ShellCommand::List => {
+                let pending = self.pending();
+                let mut text = String::from("Exercises that are not done yet:\n");
+                for i in pending {
+                    let skipped = if self.store.is_skipped(&self.exercises[i]) {
+                        " (skipped)"
+                    } else {
+                        ""
+                    };
+                    text += &format!(
+                        "  {:<17} {}{}\n",
+                        self.exercises[i].name,
+                        self.exercises[i].path.display(),
+                        skipped
+                    );
+                }
+                self.show_text(text);
+            }
+            ShellCommand::Path => self.status = exercise.path.display().to_string(),
+            ShellCommand::Clear => {}
+            ShellCommand::Quit => return true,
+            ShellCommand::Help => self.show_text(shell::HELP.to_string()),
+        }
+        false
+    }
+
+    // Show some text in place of the outcome, until the next one comes in
+    fn show_text(&mut self, text: String) {
+        self.output = Text::raw(text);
+        self.scroll = 0;
+    }
+
+    // Turn the outcome of the current exercise into colored text.
+    // The text is built with the same styles as on the command line.
+    fn render_outcome(&mut self) {
+        let Some(outcome) = &self.outcome else {
+            return;
+        };
+        let exercise = &self.exercises[self.current];
+        let text = match outcome {
+            Outcome::CompileFailed(output) => {
+                let errors = diagnostics::errors(&output.diagnostics);
+                let details = if self.show_all_errors || errors.is_empty() {
+                    output.stderr.clone()
+                } else if errors.len() > 1 {
+                    format!(
+                        "{}\n\nPress e to see them in full.",
+                        diagnostics::condense(&errors)
+                    )
+                } else {
+                    diagnostics::condense(&errors)
+                };
+                format!(
+                    "{}\n\n{}",
+                    style(format!(
+                        "Compiling of {} failed! Please try again.",
+                        exercise
+                    ))
+                    .red(),
+                    details
+                )
+            }
+            Outcome::TimedOut(output) => format!(
+                "{}\n{}",
+                style(format!("Running {} timed out!", exercise)).red(),
+                timeout_details(exercise, output)
+            ),
+            Outcome::WrongOutput(output, expected) => format!(
+                "{}\n{}",
+                style(format!(
+                    "Ran {}, but it didn't print the expected output",
+                    exercise
+                ))
+                .red(),
+                wrong_output_details(expected, output)
+            ),
+            Outcome::Tampered(changes) => format!(
+                "{}\n{}",
+                style(format!("The tests of {} were changed!", exercise)).red(),
+                tampering_details(changes)
+            ),
+            Outcome::BrokenRules(broken) => format!(
+                "{}\n{}",
+                style(format!(
+                    "{} compiles, but doesn't follow the rules of the exercise!",
+                    exercise
+                ))
+                .red(),
+                broken_rules_details(broken)
+            ),
+            Outcome::RunFailed(output) => {
+                let results = libtest::parse(&output.stdout);
+                let details = if results.is_empty() || self.show_all_errors {
+                    format!("{}\n{}", output.stdout, output.stderr)
+                } else {
+                    libtest::checklist(&results)
+                };
+                let message = match exercise.mode {
+                    Mode::Test | Mode::Cargo => format!("Testing of {} failed!", exercise),
+                    _ => format!("Ran {} with errors", exercise),
+                };
+                format!("{}\n\n{}", style(message).red(), details)
+            }
+            Outcome::Passed(outputs) => passed(exercise, outputs),
+        };
+        self.output = text.into_text().unwrap_or_else(|_| Text::raw(text));
+    }
+
+    // The hints of the current exercise revealed so far
+    fn hints(&self) -> String {
+        let exercise = &self.exercises[self.current];
+        let hints = exercise.hints();
+        if hints.is_empty() {
+            return format!("There are no hints for {}.", exercise);
+        }
+        let revealed = self.store.hints_used(exercise).min(hints.len());
+        let mut text = String::new();
+        for (i, hint) in hints[..revealed].iter().enumerate() {
+            if hints.len() > 1 {
+                text += &format!("Hint {} of {}:\n", i + 1, hints.len());
+            }
+            text += &format!("{}\n\n", hint.trim_end());
+        }
+        if revealed < hints.len() {
+            text += "Press h to reveal the next hint.";
+        }
+        text
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(2)]).areas(frame.area());
+        let [sidebar, pane] =
+            Layout::horizontal([Constraint::Length(30), Constraint::Min(0)]).areas(main);
+
+        self.draw_sidebar(frame, sidebar);
+
+        let exercise = &self.exercises[self.current];
+        let pane = if self.show_hint {
+            let [pane, hint] =
+                Layout::vertical([Constraint::Min(0), Constraint::Percentage(30)]).areas(pane);
+            let hint_block = Block::default().borders(Borders::ALL).title(" Hints ");
+            frame.render_widget(
+                Paragraph::new(self.hints())
+                    .wrap(Wrap { trim: false })
+                    .block(hint_block),
+                hint,
+            );
+            pane
+        } else {
+            pane
+        };
+        let title = match &self.running {
+            Some(name) => format!(" {} - checking {}... ", exercise, name),
+            None => format!(" {} ", exercise),
+        };
+        frame.render_widget(
+            Paragraph::new(self.output.clone())
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0))
+                .block(Block::default().borders(Borders::ALL).title(title)),
+            pane,
+        );
+
+        let num_done = self.done.iter().filter(|&&done| done).count();
+        let progress = format!(
+            "Progress: {}/{} exercises ({:.0} %)",
+            num_done,
+            self.exercises.len(),
+            num_done as f32 / self.exercises.len() as f32 * 100.0
+        );
+        let status = if let Some(command) = &self.command {
+            Line::from(format!(":{}", command))
+        } else if self.status.is_empty() {
+            Line::from(Span::styled(
+                KEYS,
+                Style::default().add_modifier(Modifier::DIM),
+            ))
+        } else {
+            Line::from(Span::styled(
+                self.status.as_str(),
+                Style::default().fg(Color::Yellow),
+            ))
+        };
+        frame.render_widget(Paragraph::new(vec![Line::from(progress), status]), footer);
+    }
+
+    // The topics along with how many of their exercises are done.
+    // The topic of the current exercise lists its exercises as well.
+    fn draw_sidebar(&self, frame: &mut Frame, area: Rect) {
+        let mut items = Vec::new();
+        let mut selected = 0;
+        for topic in &self.topics {
+            let num_done = topic.exercises.iter().filter(|&&i| self.done[i]).count();
+            let finished = num_done == topic.exercises.len();
+            let marker = if finished { "✓" } else { " " };
+            let style = if finished {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default()
+            };
+            items.push(
+                ListItem::new(format!(
+                    "{} {} {}/{}",
+                    marker,
+                    topic.name,
+                    num_done,
+                    topic.exercises.len()
+                ))
+                .style(style),
+            );
+
+            if !topic.exercises.contains(&self.current) {
+                continue;
+            }
+            for &i in &topic.exercises {
+                let (marker, style) = if i == self.current {
+                    ("▶", Style::default().add_modifier(Modifier::BOLD))
+                } else if self.done[i] {
+                    ("✓", Style::default().fg(Color::Green))
+                } else {
+                    ("·", Style::default())
+                };
+                if i == self.current {
+                    selected = items.len();
+                }
+                items.push(
+                    ListItem::new(format!("   {} {}", marker, self.exercises[i].name)).style(style),
+                );
+            }
+        }
+
+        let mut state = ListState::default().with_selected(Some(selected));
+        frame.render_stateful_widget(
+            List::new(items).block(Block::default().borders(Borders::ALL).title(" Topics ")),
+            area,
+            &mut state,
+        );
+    }
+}
+
+// What to show for an exercise that compiled and passed: its output, and
+// how to move on if it is still marked as not done
+fn passed(exercise: &Exercise, outputs: &[ExerciseOutput]) -> String {
+    let message = match exercise.mode {
+        Mode::Compile => format!("Successfully ran {}!", exercise),
+        Mode::Test | Mode::Cargo => format!("Successfully tested {}!", exercise),
+        Mode::Clippy => format!("Successfully compiled {}!", exercise),
+    };
+    let mut text = format!("{}\n", style(message).green());
+
+    if let Mode::Compile = exercise.mode {
+        text += "\nOutput:\n";
+        for output in outputs {
+            text += &output.stdout;
+        }
+    }
+
+    if let State::Pending(context) = exercise.state() {
+        text += &format!(
+            "\nYou can keep working on this exercise,\nor jump into the next one by removing the {} comment:\n\n",
+            style("`I AM NOT DONE`").bold()
+        );
+        for line in context {
+            let formatted = if line.important {
+                style(line.line).bold().to_string()
+            } else {
+                line.line
+            };
+            text += &format!(
+                "{:>2} {}  {}\n",
+                style(line.number).blue().bold(),
+                style("|").blue(),
+                formatted
+            );
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn exercise(path: &str) -> Exercise {
+        Exercise {
+            name: path.into(),
+            path: PathBuf::from(path),
+            mode: Mode::Compile,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_topics_group_exercises_by_directory() {
+        let exercises = vec![
+            exercise("exercises/intro/intro1.rs"),
+            exercise("exercises/intro/intro2.rs"),
+            exercise("exercises/variables/variables1.rs"),
+            exercise("exercises/quiz1.rs"),
+        ];
+
+        let topics: Vec<(String, Vec<usize>)> = topics(&exercises)
+            .into_iter()
+            .map(|topic| (topic.name, topic.exercises))
+            .collect();
+        assert_eq!(
+            topics,
+            vec![
+                ("intro".to_string(), vec![0, 1]),
+                ("variables".to_string(), vec![2]),
+                ("exercises".to_string(), vec![3]),
+            ]
+        );
+    }
+}
@@ -1,18 +1,96 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
+use crate::diagnostics::{self, print_errors};
+use crate::diff::diff;
+use crate::exercise::{normalize_output, Exercise, ExerciseOutput, Mode, State};
+use crate::libtest::{self, print_checklist};
+use crate::progress::ProgressStore;
+use crate::rules;
+use crate::tamper;
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
+use std::collections::HashMap;
 use std::env;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
+use std::thread;
+use std::time::{Duration, Instant};
 
 // Verify that the provided container of Exercise objects
 // can be compiled and run without any failures.
 // Any such failures will be reported to the end user.
 // If the Exercise being verified is a test, the verbose boolean
 // determines whether or not the test harness outputs are displayed.
+// It also shows all compiler errors instead of only the first one.
+// Every attempt is recorded in the given ProgressStore.
+// With more than one job, exercises are compiled on a pool of worker
+// threads, but still reported in the given order.
 pub fn verify<'a>(
     exercises: impl IntoIterator<Item = &'a Exercise>,
     progress: (usize, usize),
+    store: &mut ProgressStore,
     verbose: bool,
+    jobs: usize,
 ) -> Result<(), &'a Exercise> {
+    let records = verify_each(exercises, progress, store, verbose, jobs, false);
+    match records.into_iter().find(|r| r.verdict != Verdict::Done) {
+        Some(record) => Err(record.exercise),
+        None => Ok(()),
+    }
+}
+
+// How verifying an exercise went
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Verdict {
+    Done,
+    // It passed, but still has the `I AM NOT DONE` comment
+    NotDone,
+    CompileFailed,
+    RunFailed,
+    TimedOut,
+    WrongOutput,
+    // The tests it came with were changed
+    Tampered,
+    // It compiled, but doesn't use what it has to, or uses what it must not
+    BrokenRules,
+    // An earlier exercise failed, so this one wasn't verified
+    NotRun,
+}
+
+impl Verdict {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verdict::Done => "done",
+            Verdict::NotDone => "not_done",
+            Verdict::CompileFailed => "compile_failed",
+            Verdict::RunFailed => "run_failed",
+            Verdict::TimedOut => "timed_out",
+            Verdict::WrongOutput => "wrong_output",
+            Verdict::Tampered => "tampered",
+            Verdict::BrokenRules => "broken_rules",
+            Verdict::NotRun => "not_run",
+        }
+    }
+}
+
+// What `verify_each` found out about an exercise
+pub struct Record<'a> {
+    pub exercise: &'a Exercise,
+    pub verdict: Verdict,
+    pub duration: Duration,
+    // The output of the compiler, the tests or the binary
+    pub output: String,
+}
+
+// Like `verify`, with a Record for every exercise. Unless `keep_going` is
+// set, it stops at the first exercise that fails or isn't done yet, and
+// the exercises after it are NotRun.
+pub fn verify_each<'a>(
+    exercises: impl IntoIterator<Item = &'a Exercise>,
+    progress: (usize, usize),
+    store: &mut ProgressStore,
+    verbose: bool,
+    jobs: usize,
+    keep_going: bool,
+) -> Vec<Record<'a>> {
     let (num_done, total) = progress;
     let bar = ProgressBar::new(total as u64);
     bar.set_style(
@@ -21,120 +99,439 @@ pub fn verify<'a>(
             .progress_chars("#>-"),
     );
     bar.set_position(num_done as u64);
+    let exercises: Vec<&Exercise> = exercises.into_iter().collect();
+    if jobs > 1 {
+        return verify_parallel(&exercises, &bar, store, verbose, jobs, keep_going);
+    }
+    let mut records = Vec::new();
+    let mut stopped = false;
     for exercise in exercises {
-        let compile_result = match exercise.mode {
-            Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose),
-            Mode::Compile => compile_and_run_interactively(exercise),
-            Mode::Clippy => compile_only(exercise),
-        };
-        if !compile_result.unwrap_or(false) {
-            return Err(exercise);
+        if stopped {
+            records.push(Record::not_run(exercise));
+            continue;
         }
-        bar.inc(1);
+        let progress_bar = spinner(exercise);
+        let start = Instant::now();
+        let outcome = evaluate(exercise, &progress_bar);
+        let duration = start.elapsed();
+        progress_bar.finish_and_clear();
+
+        let record = conclude(exercise, outcome, duration, store, verbose);
+        if record.verdict == Verdict::Done {
+            bar.inc(1);
+        } else {
+            stopped = !keep_going;
+        }
+        records.push(record);
     }
-    Ok(())
+    records
 }
 
-enum RunMode {
-    Interactive,
-    NonInteractive,
+// Compile and run the exercises on `jobs` worker threads.
+// The results are reported in order as soon as they are available,
+// stopping at the first exercise that fails or isn't done yet, unless
+// `keep_going` is set.
+fn verify_parallel<'a>(
+    exercises: &[&'a Exercise],
+    bar: &ProgressBar,
+    store: &mut ProgressStore,
+    verbose: bool,
+    jobs: usize,
+    keep_going: bool,
+) -> Vec<Record<'a>> {
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let (tx, rx) = channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(exercises.len()) {
+            let (tx, next, stop) = (tx.clone(), &next, &stop);
+            scope.spawn(move || {
+                while !stop.load(Ordering::SeqCst) {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(exercise) = exercises.get(i) else {
+                        break;
+                    };
+                    let start = Instant::now();
+                    let outcome = evaluate(exercise, &ProgressBar::hidden());
+                    if tx.send((i, (outcome, start.elapsed()))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        let mut records = Vec::new();
+        let mut finished = HashMap::new();
+        for (i, exercise) in exercises.iter().enumerate() {
+            if stop.load(Ordering::SeqCst) {
+                records.push(Record::not_run(exercise));
+                continue;
+            }
+            let (outcome, duration) = loop {
+                if let Some(evaluated) = finished.remove(&i) {
+                    break evaluated;
+                }
+                let (j, evaluated) = rx.recv().expect("A verification worker stopped early");
+                finished.insert(j, evaluated);
+            };
+            let record = conclude(exercise, outcome, duration, store, verbose);
+            if record.verdict == Verdict::Done {
+                bar.inc(1);
+            } else if !keep_going {
+                stop.store(true, Ordering::SeqCst);
+            }
+            records.push(record);
+        }
+        records
+    })
 }
 
-// Compile and run the resulting test harness of the given Exercise
-pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
-    compile_and_test(exercise, RunMode::NonInteractive, verbose)?;
-    Ok(())
+// Report the outcome of the exercise to the learner and record the attempt
+fn conclude<'a>(
+    exercise: &'a Exercise,
+    outcome: Outcome,
+    duration: Duration,
+    store: &mut ProgressStore,
+    verbose: bool,
+) -> Record<'a> {
+    let output = details(exercise, &outcome);
+    let verdict = match report(exercise, &outcome, RunMode::Interactive, verbose) {
+        Ok(true) => Verdict::Done,
+        Ok(false) => Verdict::NotDone,
+        Err(()) => judge(exercise, &outcome),
+    };
+    store.record(
+        exercise,
+        matches!(verdict, Verdict::Done | Verdict::NotDone),
+    );
+    Record {
+        exercise,
+        verdict,
+        duration,
+        output,
+    }
 }
 
-// Invoke the rust compiler without running the resulting binary
-fn compile_only(exercise: &Exercise) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {}...", exercise));
-    progress_bar.enable_steady_tick(100);
+// The verdict on the outcome of an exercise, without reporting it
+pub fn judge(exercise: &Exercise, outcome: &Outcome) -> Verdict {
+    match outcome {
+        Outcome::Passed(_) if exercise.looks_done() => Verdict::Done,
+        Outcome::Passed(_) => Verdict::NotDone,
+        Outcome::CompileFailed(_) => Verdict::CompileFailed,
+        Outcome::RunFailed(_) => Verdict::RunFailed,
+        Outcome::TimedOut(_) => Verdict::TimedOut,
+        Outcome::WrongOutput(..) => Verdict::WrongOutput,
+        Outcome::Tampered(_) => Verdict::Tampered,
+        Outcome::BrokenRules(_) => Verdict::BrokenRules,
+    }
+}
 
-    let _ = compile(exercise, &progress_bar)?;
-    progress_bar.finish_and_clear();
+impl<'a> Record<'a> {
+    fn not_run(exercise: &'a Exercise) -> Record<'a> {
+        Record {
+            exercise,
+            verdict: Verdict::NotRun,
+            duration: Duration::ZERO,
+            output: String::new(),
+        }
+    }
+}
 
-    Ok(prompt_for_completion(exercise, None))
+enum RunMode {
+    Interactive,
+    NonInteractive,
 }
 
-// Compile the given Exercise and run the resulting binary in an interactive mode
-fn compile_and_run_interactively(exercise: &Exercise) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {}...", exercise));
-    progress_bar.enable_steady_tick(100);
+// What happened when compiling and running an Exercise
+pub enum Outcome {
+    // The exercise didn't compile (or Clippy wasn't happy)
+    CompileFailed(ExerciseOutput),
+    // The binary or the test harness exited with an error
+    RunFailed(ExerciseOutput),
+    // The binary or the test harness ran for too long and was stopped
+    TimedOut(ExerciseOutput),
+    // The binary ran fine, but didn't print the expected output (given second)
+    WrongOutput(ExerciseOutput, String),
+    // The tests of a test mode exercise were changed, in the given ways,
+    // so it wasn't even compiled
+    Tampered(Vec<String>),
+    // The exercise compiled, but breaks the given `require` and `forbid`
+    // rules of its info.toml, so it wasn't run
+    BrokenRules(Vec<String>),
+    // Everything went fine, with the output of every run. Clippy exercises
+    // are not run, so have none
+    Passed(Vec<ExerciseOutput>),
+}
 
-    let compilation = compile(exercise, &progress_bar)?;
+// Compile the given Exercise and run it once, the way `verify` would,
+// then report the outcome without waiting for the learner
+pub fn run_once(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
+    verify_exercise(exercise, RunMode::NonInteractive, verbose)?;
+    Ok(())
+}
 
-    progress_bar.set_message(format!("Running {}...", exercise));
-    let result = compilation.run();
+// Compile the given Exercise and run the resulting binary or test harness,
+// showing a spinner in the meantime, then report the outcome
+fn verify_exercise(exercise: &Exercise, run_mode: RunMode, verbose: bool) -> Result<bool, ()> {
+    let progress_bar = spinner(exercise);
+    let outcome = evaluate(exercise, &progress_bar);
     progress_bar.finish_and_clear();
 
-    let output = match result {
-        Ok(output) => output,
-        Err(output) => {
-            warn!("Ran {} with errors", exercise);
-            println!("{}", output.stdout);
-            println!("{}", output.stderr);
-            return Err(());
-        }
-    };
-
-    Ok(prompt_for_completion(exercise, Some(output.stdout)))
+    report(exercise, &outcome, run_mode, verbose)
 }
 
-// Compile the given Exercise as a test harness and display
-// the output if verbose is set to true
-fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool) -> Result<bool, ()> {
+fn spinner(exercise: &Exercise) -> ProgressBar {
     let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Testing {}...", exercise));
+    match exercise.mode {
+        Mode::Test | Mode::Cargo => progress_bar.set_message(format!("Testing {}...", exercise)),
+        _ => progress_bar.set_message(format!("Compiling {}...", exercise)),
+    }
     progress_bar.enable_steady_tick(100);
+    progress_bar
+}
 
-    let compilation = compile(exercise, &progress_bar)?;
-    let result = compilation.run();
-    progress_bar.finish_and_clear();
-
-    match result {
-        Ok(output) => {
-            if verbose {
-                println!("{}", output.stdout);
-            }
-            if let RunMode::Interactive = run_mode {
-                Ok(prompt_for_completion(exercise, None))
-            } else {
-                Ok(true)
+// Compile the given Exercise and run it, unless it is a Clippy exercise.
+// Nothing is printed here, so this is safe to call from worker threads.
+pub fn evaluate(exercise: &Exercise, progress_bar: &ProgressBar) -> Outcome {
+    let changes = tamper::check(exercise);
+    if !changes.is_empty() {
+        return Outcome::Tampered(changes);
+    }
+    let compilation = match exercise.compile() {
+        Ok(compilation) => compilation,
+        Err(output) => return Outcome::CompileFailed(output),
+    };
+    let broken = rules::check(exercise);
+    if !broken.is_empty() {
+        return Outcome::BrokenRules(broken);
+    }
+    match exercise.mode {
+        Mode::Clippy => Outcome::Passed(Vec::new()),
+        Mode::Test | Mode::Cargo => match compilation.run() {
+            Ok(output) => Outcome::Passed(vec![output]),
+            Err(output) if output.timed_out => Outcome::TimedOut(output),
+            Err(output) => Outcome::RunFailed(output),
+        },
+        Mode::Compile => {
+            progress_bar.set_message(format!("Running {}...", exercise));
+            let mut outputs = Vec::new();
+            // Stop at the first case that fails, there's no point in running the rest
+            for case in exercise.cases() {
+                let case = match case.load() {
+                    Ok(case) => case,
+                    Err(problem) => return Outcome::RunFailed(ExerciseOutput::unreadable(problem)),
+                };
+                let output = match compilation.run_case(&case) {
+                    Ok(output) => output,
+                    Err(output) if output.timed_out => return Outcome::TimedOut(output),
+                    Err(output) => return Outcome::RunFailed(output),
+                };
+                if let Err(expected) = case.check_output(&output.stdout) {
+                    return Outcome::WrongOutput(output, expected);
+                }
+                outputs.push(output);
             }
+            Outcome::Passed(outputs)
         }
-        Err(output) => {
+    }
+}
+
+// Report the outcome of compiling and running the given Exercise.
+// Returns whether the exercise is done, or an error if it failed.
+fn report(
+    exercise: &Exercise,
+    outcome: &Outcome,
+    run_mode: RunMode,
+    verbose: bool,
+) -> Result<bool, ()> {
+    match (outcome, exercise.mode) {
+        (Outcome::CompileFailed(output), _) => {
             warn!(
-                "Testing of {} failed! Please try again. Here's the output:",
+                "Compiling of {} failed! Please try again. Here's the output:",
                 exercise
             );
-            println!("{}", output.stdout);
+            print_errors(&output.diagnostics, &output.stderr, verbose);
             Err(())
         }
-    }
-}
-
-// Compile the given Exercise and return an object with information
-// about the state of the compilation
-fn compile<'a, 'b>(
-    exercise: &'a Exercise,
-    progress_bar: &'b ProgressBar,
-) -> Result<CompiledExercise<'a>, ()> {
-    let compilation_result = exercise.compile();
-
-    match compilation_result {
-        Ok(compilation) => Ok(compilation),
-        Err(output) => {
-            progress_bar.finish_and_clear();
+        (Outcome::Tampered(changes), _) => {
+            warn!("The tests of {} were changed!", exercise);
+            println!("{}", tampering_details(changes));
+            Err(())
+        }
+        (Outcome::BrokenRules(broken), _) => {
             warn!(
-                "Compiling of {} failed! Please try again. Here's the output:",
+                "{} compiles, but doesn't follow the rules of the exercise!",
                 exercise
             );
+            println!("{}", broken_rules_details(broken));
+            Err(())
+        }
+        (Outcome::TimedOut(output), _) => {
+            report_timeout(exercise, output);
+            Err(())
+        }
+        (Outcome::WrongOutput(output, expected), _) => {
+            report_wrong_output(exercise, expected, output);
+            Err(())
+        }
+        (Outcome::RunFailed(output), Mode::Test | Mode::Cargo) => {
+            let results = libtest::parse(&output.stdout);
+            if verbose || results.is_empty() {
+                warn!(
+                    "Testing of {} failed! Please try again. Here's the output:",
+                    exercise
+                );
+                println!("{}", output.stdout);
+            } else {
+                warn!(
+                    "Testing of {} failed! Please try again. Here's how the tests did:",
+                    exercise
+                );
+                print_checklist(&results);
+                println!();
+            }
+            Err(())
+        }
+        (Outcome::RunFailed(output), _) => {
+            warn!("Ran {} with errors", exercise);
+            report_input(output);
+            println!("{}", output.stdout);
             println!("{}", output.stderr);
             Err(())
         }
+        (Outcome::Passed(outputs), Mode::Test | Mode::Cargo) => {
+            if verbose {
+                for output in outputs {
+                    println!("{}", output.stdout);
+                }
+            }
+            match run_mode {
+                RunMode::Interactive => Ok(prompt_for_completion(exercise, None)),
+                RunMode::NonInteractive => Ok(true),
+            }
+        }
+        (Outcome::Passed(_), Mode::Clippy) => match run_mode {
+            RunMode::Interactive => Ok(prompt_for_completion(exercise, None)),
+            RunMode::NonInteractive => {
+                success!("Successfully compiled {}", exercise);
+                Ok(true)
+            }
+        },
+        (Outcome::Passed(outputs), _) => {
+            let stdout: Vec<&str> = outputs
+                .iter()
+                .map(|output| output.stdout.as_str())
+                .collect();
+            match run_mode {
+                RunMode::Interactive => {
+                    Ok(prompt_for_completion(exercise, Some(stdout.join("\n"))))
+                }
+                RunMode::NonInteractive => {
+                    println!("{}", stdout.join("\n"));
+                    success!("Successfully ran {}", exercise);
+                    Ok(true)
+                }
+            }
+        }
+    }
+}
+
+// What the exercise printed, or why it didn't pass
+pub fn details(exercise: &Exercise, outcome: &Outcome) -> String {
+    match outcome {
+        Outcome::CompileFailed(output) => {
+            let errors = diagnostics::errors(&output.diagnostics);
+            if errors.is_empty() {
+                output.stderr.clone()
+            } else {
+                diagnostics::condense(&errors)
+            }
+        }
+        Outcome::RunFailed(output) => format!("{}\n{}", output.stdout, output.stderr),
+        Outcome::TimedOut(output) => timeout_details(exercise, output),
+        Outcome::WrongOutput(output, expected) => wrong_output_details(expected, output),
+        Outcome::Tampered(changes) => tampering_details(changes),
+        Outcome::BrokenRules(broken) => broken_rules_details(broken),
+        Outcome::Passed(outputs) => {
+            let stdout: Vec<&str> = outputs
+                .iter()
+                .map(|output| output.stdout.as_str())
+                .collect();
+            stdout.join("\n")
+        }
+    }
+}
+
+// Explain that the exercise was stopped, instead of showing a plain failure
+pub fn report_timeout(exercise: &Exercise, output: &ExerciseOutput) {
+    warn!("Running {} timed out!", exercise);
+    println!("{}", timeout_details(exercise, output));
+}
+
+pub fn timeout_details(exercise: &Exercise, output: &ExerciseOutput) -> String {
+    format!(
+        "It was stopped after {} seconds, or after using up its CPU time.\n\
+         Look for a loop that never ends, or for threads that wait on each other forever.\n\
+         {}Here's what it printed before it was stopped:\n{}",
+        exercise.timeout().as_secs(),
+        input_details(output),
+        output.stdout
+    )
+}
+
+pub fn tampering_details(changes: &[String]) -> String {
+    let changes: Vec<String> = changes
+        .iter()
+        .map(|change| format!("- {}", change))
+        .collect();
+    format!(
+        "{}\nThe exercise only counts with the tests it came with, please put them back.\n\
+         `rustlings reset` shows everything that was changed.",
+        changes.join("\n")
+    )
+}
+
+pub fn broken_rules_details(broken: &[String]) -> String {
+    let broken: Vec<String> = broken.iter().map(|rule| format!("- {}", rule)).collect();
+    format!(
+        "{}\nThe exercise is meant to practice these, so it only counts when it follows them.",
+        broken.join("\n")
+    )
+}
+
+// Show how the output of the exercise differs from the expected one
+pub fn report_wrong_output(exercise: &Exercise, expected: &str, output: &ExerciseOutput) {
+    warn!("Ran {}, but it didn't print the expected output", exercise);
+    println!("{}", wrong_output_details(expected, output));
+}
+
+pub fn wrong_output_details(expected: &str, output: &ExerciseOutput) -> String {
+    format!(
+        "{}Lines marked with {} are missing, lines marked with {} were not expected:\n{}",
+        input_details(output),
+        style("-").red(),
+        style("+").green(),
+        diff(expected, &normalize_output(&output.stdout))
+    )
+}
+
+// Show the input the exercise was given, so that a failing case
+// can be told apart from the others and tried out by hand
+pub fn report_input(output: &ExerciseOutput) {
+    print!("{}", input_details(output));
+}
+
+fn input_details(output: &ExerciseOutput) -> String {
+    match &output.input {
+        Some(input) => format!(
+            "It was given this input:\n{}\n{}\n{}\n",
+            separator(),
+            input.trim_end(),
+            separator()
+        ),
+        None => String::new(),
     }
 }
 
@@ -146,7 +543,7 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>) ->
 
     match exercise.mode {
         Mode::Compile => success!("Successfully ran {}!", exercise),
-        Mode::Test => success!("Successfully tested {}!", exercise),
+        Mode::Test | Mode::Cargo => success!("Successfully tested {}!", exercise),
         Mode::Clippy => success!("Successfully compiled {}!", exercise),
     }
 
@@ -160,7 +557,7 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>) ->
 
     let success_msg = match exercise.mode {
         Mode::Compile => "The code is compiling!",
-        Mode::Test => "The code is compiling, and the tests pass!",
+        Mode::Test | Mode::Cargo => "The code is compiling, and the tests pass!",
         Mode::Clippy => clippy_success_msg,
     };
 
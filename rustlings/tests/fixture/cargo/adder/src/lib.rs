@@ -0,0 +1,14 @@
+mod math;
+
+pub use math::add;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds() {
+        println!("THE CARGO TESTS PASS");
+        assert_eq!(add(1, 2), 3);
+    }
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let x: i32 = "one";
+    missing_function();
+    println!("{}", undefined_variable);
+}
@@ -0,0 +1,10 @@
+// Prints the sum of the two numbers on every line of its input
+use std::io::{self, BufRead};
+
+fn main() {
+    for line in io::stdin().lock().lines() {
+        let line = line.unwrap();
+        let sum: i32 = line.split_whitespace().map(|n| n.parse::<i32>().unwrap()).sum();
+        println!("{}", sum);
+    }
+}
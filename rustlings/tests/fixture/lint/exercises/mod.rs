@@ -0,0 +1,4 @@
+mod forgotten;
+mod intro;
+mod no_tests;
+mod only_declared;
@@ -0,0 +1,3 @@
+fn main() {
+    println!("There are no tests here");
+}
@@ -0,0 +1,6 @@
+fn main() {
+    println!("| n | n^2 |");
+    for n in 1..=3 {
+        println!("| {} | {:>3} |", n, n * n);
+    }
+}
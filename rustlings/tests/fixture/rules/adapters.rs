@@ -0,0 +1,13 @@
+fn doubled(numbers: &[i32]) -> Vec<i32> {
+    numbers.iter().map(|n| n * 2).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubles() {
+        assert_eq!(doubled(&[1, 2]), vec![2, 4]);
+    }
+}
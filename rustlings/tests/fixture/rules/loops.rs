@@ -0,0 +1,17 @@
+fn total(numbers: &[&str]) -> i32 {
+    let mut total = 0;
+    for n in numbers {
+        total += n.parse::<i32>().unwrap();
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds() {
+        assert_eq!(total(&["1", "2"]), 3);
+    }
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let number: i32 = "42".parse().unwrap();
+    println!("{}", number);
+}
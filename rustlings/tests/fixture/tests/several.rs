@@ -0,0 +1,23 @@
+#[test]
+fn passes() {
+    assert!(true);
+}
+
+#[test]
+fn fails_assert() {
+    assert_eq!(1 + 1, 3);
+}
+
+#[test]
+fn panics() {
+    panic!("Something went wrong");
+}
+
+#[test]
+fn returns_err() -> Result<(), String> {
+    Err("not a number".to_string())
+}
+
+#[test]
+#[ignore]
+fn ignored() {}
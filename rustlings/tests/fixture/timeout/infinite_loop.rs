@@ -0,0 +1,4 @@
+fn main() {
+    println!("Looping forever");
+    loop {}
+}
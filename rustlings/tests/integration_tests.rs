@@ -3,7 +3,9 @@ use glob::glob;
 use predicates::boolean::PredicateBooleanExt;
 use std::fs::File;
 use std::io::Read;
+use std::path::Path;
 use std::process::Command;
+use std::time::{Duration, Instant};
 
 #[test]
 fn runs_without_arguments() {
@@ -202,6 +204,12 @@ fn run_rustlings_list() {
 
 #[test]
 fn run_rustlings_list_no_pending() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["list"])
@@ -213,6 +221,12 @@ fn run_rustlings_list_no_pending() {
 
 #[test]
 fn run_rustlings_list_both_done_and_pending() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "finished_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success();
     Command::cargo_bin("rustlings")
         .unwrap()
         .args(&["list"])
@@ -243,3 +257,734 @@ fn run_rustlings_list_without_done() {
         .success()
         .stdout(predicates::str::contains("Done").not());
 }
+
+#[test]
+fn run_rustlings_list_removed_marker_is_not_done() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--solved", "--names"])
+        .current_dir("tests/fixture/unverified")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("unverified_exercise").not());
+}
+
+#[test]
+fn reset_requires_force_and_restores_original() {
+    let path = "tests/fixture/reset/reset_exercise.rs";
+    let original = std::fs::read_to_string(path).unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("list")
+        .current_dir("tests/fixture/reset")
+        .assert()
+        .success();
+    std::fs::write(path, "fn main() {}\n").unwrap();
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "reset_exercise"])
+        .current_dir("tests/fixture/reset")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("-fn main() {}"));
+    assert_eq!(std::fs::read_to_string(path).unwrap(), "fn main() {}\n");
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "reset_exercise", "--force"])
+        .current_dir("tests/fixture/reset")
+        .assert()
+        .success();
+    assert_eq!(std::fs::read_to_string(path).unwrap(), original);
+}
+
+#[test]
+fn verify_in_parallel_all_success() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--jobs", "4"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
+}
+
+#[test]
+fn verify_in_parallel_reports_first_failure_in_order() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--jobs", "4"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("Compiling of compFailure.rs failed")
+                .and(predicates::str::contains("testFailure.rs").not()),
+        );
+}
+
+#[test]
+fn verify_stops_exercise_that_times_out() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/timeout")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("Running infinite_loop.rs timed out!")
+                .and(predicates::str::contains("Looping forever")),
+        );
+}
+
+#[test]
+fn run_single_compile_failure_shows_first_error_only() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compManyErrors"])
+        .current_dir("tests/fixture/failure/")
+        .env("NO_COLOR", "1")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("and 2 more errors")
+                .and(predicates::str::contains("rustlings explain E0425"))
+                .and(predicates::str::contains("aborting due to").not()),
+        );
+}
+
+#[test]
+fn run_single_compile_failure_with_all_errors() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--nocapture", "run", "compManyErrors"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "aborting due to 3 previous errors",
+        ));
+}
+
+#[test]
+fn explain_error_code() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["explain", "E0308"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Expected type did not match the received type",
+        ));
+}
+
+#[test]
+fn run_compile_exercise_with_expected_output() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "table_from_file"])
+        .current_dir("tests/fixture/output")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_compile_exercise_with_unexpected_output() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "table_inline"])
+        .current_dir("tests/fixture/output")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("didn't print the expected output")
+                .and(predicates::str::contains("+| 3 |   9 |")),
+        );
+}
+
+#[test]
+fn run_exercise_with_input() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "sum_single"])
+        .current_dir("tests/fixture/input")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::starts_with("3\n")
+                .and(predicates::str::contains("Successfully ran sum.rs")),
+        );
+}
+
+#[test]
+fn run_exercise_with_cases() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "sum_cases"])
+        .current_dir("tests/fixture/input")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("30\n0"));
+}
+
+#[test]
+fn run_exercise_with_failing_case_shows_its_input() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "sum_wrong_case"])
+        .current_dir("tests/fixture/input")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("It was given this input:")
+                .and(predicates::str::contains("5 5"))
+                .and(predicates::str::contains("-11"))
+                .and(predicates::str::contains("+10")),
+        );
+}
+
+#[test]
+fn run_cargo_exercise_with_output() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--nocapture", "run", "adder"])
+        .current_dir("tests/fixture/cargo")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("THE CARGO TESTS PASS"));
+}
+
+#[test]
+fn run_cargo_exercise_with_failing_test() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "broken"])
+        .current_dir("tests/fixture/cargo")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("Testing of broken failed"));
+}
+
+#[test]
+fn run_cargo_exercise_stops_tests_that_time_out() {
+    let start = Instant::now();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "sleepy"])
+        .current_dir("tests/fixture/cargo")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("Running sleepy timed out!"));
+    // The test binary cargo started has to be stopped too
+    assert!(start.elapsed() < Duration::from_secs(30));
+}
+
+#[test]
+fn run_test_exercise_shows_checklist() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "several"])
+        .current_dir("tests/fixture/tests")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("✓ passes")
+                .and(predicates::str::contains("✗ panics"))
+                .and(predicates::str::contains("Something went wrong"))
+                .and(predicates::str::contains("1 of 4 tests pass.")),
+        );
+}
+
+#[test]
+fn run_single_test_of_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "several", "--test", "passes"])
+        .current_dir("tests/fixture/tests")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("✓ passes").and(predicates::str::contains("panics").not()),
+        );
+}
+
+#[test]
+fn run_single_test_with_unknown_name() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "several", "--test", "nothing"])
+        .current_dir("tests/fixture/tests")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "No test of this exercise matches",
+        ));
+}
+
+#[test]
+fn hints_are_revealed_one_at_a_time() {
+    let _ = std::fs::remove_file("tests/fixture/hints/.rustlings-state.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "leveled"])
+        .current_dir("tests/fixture/hints")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("Hint 1 of 3")
+                .and(predicates::str::contains("Hint 2 of 3").not())
+                .and(predicates::str::contains("reveal the next one")),
+        );
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "leveled"])
+        .current_dir("tests/fixture/hints")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("Hint 1 of 3")
+                .and(predicates::str::contains("Variables have to be declared")),
+        );
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list"])
+        .current_dir("tests/fixture/hints")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("2/3"));
+}
+
+#[test]
+fn solution_is_shown_next_to_done_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "solved"])
+        .current_dir("tests/fixture/solution")
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["solution", "solved"])
+        .current_dir("tests/fixture/solution")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("solutions/solved.rs")
+                .and(predicates::str::contains("println!(\"{x}\");")),
+        );
+}
+
+#[test]
+fn solution_requires_exercise_to_be_done() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["solution", "unsolved"])
+        .current_dir("tests/fixture/solution")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("--give-up"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["solution", "unsolved", "--give-up"])
+        .current_dir("tests/fixture/solution")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("let x = 5;"));
+}
+
+#[test]
+fn solution_of_exercise_without_one() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["solution", "without_solution", "--give-up"])
+        .current_dir("tests/fixture/solution")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("has no reference solution"));
+}
+
+#[test]
+fn check_solution_that_passes() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["check-solutions", "unsolved"])
+        .current_dir("tests/fixture/solution")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("1 of 1 solutions check out"));
+}
+
+#[test]
+fn check_solution_that_fails() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["check-solutions", "broken_solution"])
+        .current_dir("tests/fixture/solution")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("The solution of unsolved.rs doesn't pass")
+                .and(predicates::str::contains("E0425")),
+        );
+}
+
+#[test]
+fn check_solution_of_exercise_that_passes_already() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["check-solutions", "solved"])
+        .current_dir("tests/fixture/solution")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("passes as it is"));
+}
+
+#[test]
+fn check_solution_of_exercise_the_learner_solved() {
+    // A copy of the course, so that solving the exercise doesn't get in the
+    // way of the other tests
+    let course = std::env::temp_dir().join(format!("rustlings-solved-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&course);
+    std::fs::create_dir_all(course.join("solutions")).unwrap();
+    for file in [
+        "info.toml",
+        "solved.rs",
+        "unsolved.rs",
+        "solutions/solved.rs",
+        "solutions/unsolved.rs",
+        "solutions/broken.rs",
+    ] {
+        std::fs::copy(
+            Path::new("tests/fixture/solution").join(file),
+            course.join(file),
+        )
+        .unwrap();
+    }
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("list")
+        .current_dir(&course)
+        .assert()
+        .success();
+
+    std::fs::copy(
+        course.join("solutions/unsolved.rs"),
+        course.join("unsolved.rs"),
+    )
+    .unwrap();
+    let checked = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["check-solutions", "unsolved"])
+        .current_dir(&course)
+        .assert();
+    std::fs::remove_dir_all(&course).unwrap();
+    checked
+        .success()
+        .stdout(predicates::str::contains("1 of 1 solutions check out"));
+}
+
+#[test]
+fn lint_course_reports_every_warning() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("lint-course")
+        .current_dir("tests/fixture/lint")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "info.toml:10: warning: no_tests is in test mode, but has no #[test]",
+        ))
+        .stdout(predicates::str::contains(
+            "exercises/forgotten.rs looks like an exercise, but isn't listed",
+        ))
+        .stdout(predicates::str::contains(
+            "exercises/only_declared.rs is declared in exercises/mod.rs",
+        ))
+        .stdout(predicates::str::contains("intro").not());
+}
+
+#[test]
+fn lint_course_looks_wherever_the_exercises_are() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("lint-course")
+        .current_dir("tests/fixture/layout")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "lessons/stray.rs looks like an exercise, but isn't listed",
+        ))
+        .stdout(predicates::str::contains("first.rs").not());
+}
+
+#[test]
+fn lint_course_fails_on_errors() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("lint-course")
+        .current_dir("tests/fixture/invalid")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "info.toml:2: warning: twice has no hint",
+        ))
+        .stdout(predicates::str::contains("2 errors, 3 warnings"));
+}
+
+#[test]
+fn every_invalid_exercise_is_reported_on_startup() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("list")
+        .current_dir("tests/fixture/invalid")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "info.toml:8: error: More than one exercise is called twice (see line 2)",
+        ))
+        .stdout(predicates::str::contains(
+            "info.toml:15: error: The exercise missing points to missing.rs",
+        ));
+}
+
+#[test]
+fn course_can_be_picked_by_directory() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--course", "tests/fixture/success", "verify"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn course_can_be_picked_by_name() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("list")
+        .env("RUSTLINGS_COURSES_DIR", "tests/fixture")
+        .env("RUSTLINGS_COURSE", "hints")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("leveled.rs"));
+}
+
+#[test]
+fn courses_are_listed() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--course", "tests/fixture/success", "courses"])
+        .env(
+            "RUSTLINGS_COURSES_DIR",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixture"),
+        )
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("▶ success"))
+        .stdout(predicates::str::contains("  failure"));
+}
+
+#[test]
+fn unknown_course_is_reported() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--course", "nothing", "list"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("nor an installed course"));
+}
+
+#[test]
+fn list_as_json() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--format", "json"])
+        .current_dir("tests/fixture/lint")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(r#""name": "no_tests""#))
+        .stdout(predicates::str::contains(r#""topic": "exercises""#))
+        .stdout(predicates::str::contains(r#""status": "pending""#))
+        .stdout(predicates::str::contains(r#""total": 2"#))
+        .stdout(predicates::str::contains("Progress:").not());
+}
+
+#[test]
+fn list_as_csv() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--format", "csv", "--filter", "intro"])
+        .current_dir("tests/fixture/lint")
+        .assert()
+        .success()
+        .stdout(
+            "name,path,mode,topic,status,attempts,hints_used,hints\n\
+             intro,exercises/intro.rs,compile,exercises,pending,0,0,1\n",
+        );
+}
+
+#[test]
+fn verify_keep_going_writes_junit_report() {
+    let report = std::env::temp_dir().join(format!("rustlings-{}.xml", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--keep-going", "--report"])
+        .arg(&report)
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("0 of 3 exercises are done."));
+    let xml = std::fs::read_to_string(&report).unwrap();
+    std::fs::remove_file(&report).unwrap();
+    assert!(xml.contains(r#"tests="3" failures="3" skipped="0""#));
+    assert!(xml.contains(r#"<failure type="compile_failed""#));
+    assert!(xml.contains(r#"<testcase name="testFailure""#));
+}
+
+#[test]
+fn verify_writes_json_report_up_to_first_failure() {
+    let report = std::env::temp_dir().join(format!("rustlings-{}.json", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--report-format", "json", "--report"])
+        .arg(&report)
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1);
+    let json = std::fs::read_to_string(&report).unwrap();
+    std::fs::remove_file(&report).unwrap();
+    assert!(json.contains(r#""status": "compile_failed""#));
+    assert!(json.contains(r#""not_run": 2"#));
+}
+
+#[test]
+fn grade_students_against_the_course() {
+    let gradebook = std::env::temp_dir().join(format!("rustlings-{}.csv", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["grade", "students/alice", "students/bob", "--output"])
+        .arg(&gradebook)
+        .current_dir("tests/fixture/grade")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "The tests of students/bob/exercises/add.rs were changed",
+        ));
+    let csv = std::fs::read_to_string(&gradebook).unwrap();
+    std::fs::remove_file(&gradebook).unwrap();
+    assert_eq!(
+        csv,
+        "student,done,total,add,hello\n\
+         students/alice,2,2,done,done\n\
+         students/bob,0,2,tampered,missing\n"
+    );
+}
+
+#[test]
+fn grade_resolves_paths_before_switching_to_the_course() {
+    let gradebook =
+        std::env::temp_dir().join(format!("rustlings-course-{}.csv", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args([
+            "--course",
+            "grade",
+            "grade",
+            "grade/students/alice",
+            "--output",
+        ])
+        .arg(&gradebook)
+        .current_dir("tests/fixture")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "grade/students/alice: 2 of 2 exercises are done.",
+        ));
+    std::fs::remove_file(&gradebook).unwrap();
+}
+
+#[test]
+fn verify_fails_when_tests_were_changed() {
+    let course = std::path::Path::new("tests/fixture/tamper");
+    let forget = || {
+        let _ = std::fs::remove_dir_all(course.join(".rustlings-originals"));
+        let _ = std::fs::remove_file(course.join(".rustlings-fingerprints.json"));
+        let _ = std::fs::remove_file(course.join(".rustlings-state.json"));
+    };
+    let paths = [course.join("guarded.rs"), course.join("editable.rs")];
+    let originals: Vec<String> = paths
+        .iter()
+        .map(|path| std::fs::read_to_string(path).unwrap())
+        .collect();
+    forget();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("list")
+        .current_dir(course)
+        .assert()
+        .success();
+    for (path, original) in paths.iter().zip(&originals) {
+        std::fs::write(path, original.replace("double(2), 4", "double(2), 2")).unwrap();
+    }
+    // The tests are still known without the copies for `rustlings reset`
+    std::fs::remove_dir_all(course.join(".rustlings-originals")).unwrap();
+
+    let assert = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--keep-going"])
+        .current_dir(course)
+        .assert();
+    for (path, original) in paths.iter().zip(&originals) {
+        std::fs::write(path, original).unwrap();
+    }
+    assert.code(1).stdout(
+        predicates::str::contains("The tests of guarded.rs were changed")
+            .and(predicates::str::contains("the test `doubles` was changed"))
+            .and(predicates::str::contains("1 of 2 exercises are done.")),
+    );
+
+    // Without the fingerprints, changes from before can't be found anymore
+    std::fs::remove_file(course.join(".rustlings-fingerprints.json")).unwrap();
+    let assert = Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("list")
+        .current_dir(course)
+        .assert();
+    forget();
+    assert.success().stdout(predicates::str::contains(
+        "The original tests of guarded.rs weren't recorded",
+    ));
+}
+
+#[test]
+fn verify_fails_when_rules_are_broken() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--keep-going"])
+        .current_dir("tests/fixture/rules")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("loops.rs compiles, but doesn't follow the rules")
+                .and(predicates::str::contains("it has to use `map`"))
+                .and(predicates::str::contains(
+                    "it must not use `unwrap`, but does on line 4 of loops.rs",
+                ))
+                .and(predicates::str::contains("adapters.rs compiles").not())
+                .and(predicates::str::contains("1 of 3 exercises are done.")),
+        );
+}
+
+#[test]
+fn run_fails_when_rules_are_broken() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "parse"])
+        .current_dir("tests/fixture/rules")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("it must not use `unwrap`, but does on line 2 of parse.rs")
+                .and(predicates::str::contains("Successfully ran").not()),
+        );
+}